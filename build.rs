@@ -1,17 +1,52 @@
 use std::{env, fs, path::PathBuf};
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use rustta_bindgen::meta::{
-    func_info::FuncInfo,
-    params::{
-        input::{Input, InputFlags, InputType},
-        opt_input::OptInputType,
-        output::{Output, OutputType},
+use rustta_bindgen::{
+    direct::TA_MAType,
+    meta::{
+        flags::FuncFlags,
+        func_info::FuncInfo,
+        history::{self, History},
+        idents::{self, FuncIdents},
+        params::{
+            input::{Input, InputFlags, InputType},
+            opt_input::{OptInput, OptInputType},
+            output::{Output, OutputType},
+        },
+        Meta,
     },
-    Meta,
 };
 
+/// List-valued parameters sharing one enum, by the end of their TA-Lib name, as every
+/// moving average type parameter takes the same values. Other lists get an enum of
+/// their own, named after the indicator and parameter.
+const SHARED_LISTS: [(&str, &str); 1] = [("MAType", "MaType")];
+
+/// Floating point type an indicator's real inputs are read as.
+#[derive(Clone, Copy)]
+enum Precision {
+    Double,
+    Single,
+}
+
+impl Precision {
+    fn real_type(self) -> TokenStream {
+        match self {
+            Precision::Double => quote! { f64 },
+            Precision::Single => quote! { f32 },
+        }
+    }
+
+    /// Prefix of the ta_func.h functions that take inputs of this precision.
+    fn direct_prefix(self) -> &'static str {
+        match self {
+            Precision::Double => "TA_",
+            Precision::Single => "TA_S_",
+        }
+    }
+}
+
 fn main() {
     let meta = Meta::new().unwrap();
     let indicator_modules = generate_indicator_modules(&meta);
@@ -23,72 +58,647 @@ fn main() {
 
 fn generate_indicator_modules(meta: &Meta) -> TokenStream {
     let mut group_modules: Vec<TokenStream> = Vec::new();
+    let (shared_enum_idents, shared_enums) = generate_shared_list_enums(meta);
 
     for (group, indicators) in meta.data.iter() {
-        let func_structs = indicators
-            .iter()
-            .map(generate_func_struct)
-            .collect::<Vec<_>>();
+        let mut func_structs = Vec::new();
+        let mut func_tests = Vec::new();
+        for indicator in indicators {
+            let idents = identifiers(indicator);
+            func_structs.push(generate_func_struct(indicator, &idents));
+            func_tests.push(generate_func_test(indicator, &idents));
+            func_tests.push(generate_output_test(indicator, &idents));
+        }
 
-        let group_ident = format_ident!("{}", rustify_name(group));
+        let group_ident = format_ident!("{}", idents::module_name(group));
         group_modules.push(quote! {
             pub mod #group_ident {
                 #![allow(unused_imports)]
+                #![allow(clippy::type_complexity)]
 
-                use std::{ffi::CString, error::Error, convert::TryFrom};
+                use std::{ffi::CString, convert::TryFrom};
                 use rustta_bindgen::meta::func_handle::FuncHandle;
                 use rustta_bindgen::meta::params::param_holder::{
-                    ParamHolder, OptInputParam, InputParam, OutputParam, Ohlc, wrap_output
+                    ParamHolder, OptInputParam, InputParam, OutputParam, Ohlc, wrap_output,
+                    required_output_size
                 };
-                use crate::input::{Open, Low, High, Close, Volume, Length};
+                use rustta_bindgen::direct;
+                use crate::input::{
+                    Open, Low, High, Close, Volume, OpenInterest, Timestamp, Length,
+                    ShortInputPolicy, InputFlags, common_length
+                };
+                use crate::error::{IndicatorError, TaError};
+                use crate::output::{IndicatorOutput, Outputs, check_output_buffer};
+                use crate::prepared::Prepared;
+                use crate::stream::{History, Stream, LastValue};
+                use crate::bars::BarLike;
+                use super::{#(#shared_enum_idents),*};
 
                 #(#func_structs)*
+
+                #[cfg(test)]
+                mod tests {
+                    use super::*;
+                    use crate::indicators::test_columns;
+                    use crate::input::Ohlcv;
+
+                    #(#func_tests)*
+                }
             }
         })
     }
 
     quote! {
+        #(#shared_enums)*
+
         #(#group_modules)*
     }
 }
 
-fn generate_func_struct(indicator: &FuncInfo) -> TokenStream {
+/// Enum for the values a list-valued parameter takes, with a variant per value named
+/// after TA-Lib's display string for it.
+fn generate_list_enum(enum_ident: &Ident, param: &OptInput) -> TokenStream {
+    let list = param.list().unwrap();
+    let variants: Vec<Ident> = list
+        .iter()
+        .map(|value| format_ident!("{}", idents::type_name(value.display_name())))
+        .collect();
+    let names: Vec<&str> = list.iter().map(|value| value.display_name()).collect();
+    let values = list.iter().map(|value| match param.param_type() {
+        OptInputType::Integer => {
+            let value = value.value() as i32;
+            quote! { #value }
+        }
+        OptInputType::Real => {
+            let value = value.value();
+            quote! { #value }
+        }
+    });
+    let value_type = match param.param_type() {
+        OptInputType::Integer => quote! { i32 },
+        OptInputType::Real => quote! { f64 },
+    };
+    let enum_doc = format!("Values TA-Lib's {} parameter takes.", param.display_name());
+
+    for (i, variant) in variants.iter().enumerate() {
+        assert!(
+            !variants[..i].contains(variant),
+            "{} has two values named {}",
+            enum_ident,
+            variant
+        );
+    }
+
+    quote! {
+        #[doc = #enum_doc]
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+        pub enum #enum_ident {
+            #(#[doc = #names] #variants),*
+        }
+
+        impl #enum_ident {
+            /// The value TA-Lib takes for this variant.
+            pub fn value(self) -> #value_type {
+                match self {
+                    #(#enum_ident::#variants => #values),*
+                }
+            }
+        }
+
+        impl std::fmt::Display for #enum_ident {
+            /// Writes TA-Lib's display string for the variant, like `SMA`.
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(match self {
+                    #(#enum_ident::#variants => #names),*
+                })
+            }
+        }
+    }
+}
+
+/// Enums for the lists in `SHARED_LISTS`, generated once for every indicator to use.
+fn generate_shared_list_enums(meta: &Meta) -> (Vec<Ident>, Vec<TokenStream>) {
+    let mut enum_idents = Vec::new();
+    let mut enums = Vec::new();
+
+    for (suffix, name) in SHARED_LISTS.iter() {
+        let params: Vec<&OptInput> = meta
+            .data
+            .values()
+            .flatten()
+            .flat_map(|indicator| indicator.params())
+            .filter(|param| param.name().ends_with(suffix) && param.list().is_some())
+            .collect();
+
+        let first = match params.first() {
+            Some(first) => first,
+            None => continue,
+        };
+        assert!(
+            params.iter().all(|param| param.list() == first.list()),
+            "Not every {} parameter takes the same values",
+            suffix
+        );
+
+        let enum_ident = format_ident!("{}", name);
+        enums.push(generate_list_enum(&enum_ident, first));
+        if *name == "MaType" {
+            enums.push(generate_ma_type_unstable_func(&enum_ident, first));
+        }
+        enum_idents.push(enum_ident);
+    }
+
+    (enum_idents, enums)
+}
+
+/// `unstable_functions` for the moving average type enum, listing the functions whose
+/// unstable period each type warms up on.
+fn generate_ma_type_unstable_func(enum_ident: &Ident, param: &OptInput) -> TokenStream {
+    let arms = param.list().unwrap().iter().map(|value| {
+        let variant = format_ident!("{}", idents::type_name(value.display_name()));
+        let functions =
+            unstable_function_ids(history::ma_type_history(TA_MAType(value.value() as _)));
+        quote! { #enum_ident::#variant => &[#(#functions),*] }
+    });
+
+    quote! {
+        impl #enum_ident {
+            /// Functions whose unstable period a moving average of this type warms up on.
+            pub(crate) fn unstable_functions(self) -> &'static [(&'static str, rustta_bindgen::direct::TA_FuncUnstId)] {
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    }
+}
+
+/// `(name, id)` of every function whose unstable period is part of `history`.
+fn unstable_function_ids(history: History) -> Vec<TokenStream> {
+    let functions = match history {
+        History::Unstable(functions) => functions,
+        History::Lookback | History::Whole => &[],
+    };
+
+    functions
+        .iter()
+        .map(|function| {
+            let id = format_ident!("TA_FUNC_UNST_{}", function);
+            quote! { (#function, rustta_bindgen::direct::TA_FuncUnstId::#id) }
+        })
+        .collect()
+}
+
+/// Enums for the indicator's list-valued parameters that aren't in `SHARED_LISTS`.
+fn generate_own_list_enums(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    indicator
+        .params()
+        .iter()
+        .filter(|param| shared_list_name(param).is_none())
+        .filter_map(|param| {
+            let enum_ident = list_enum_ident(indicator, idents, param)?;
+            Some(generate_list_enum(&enum_ident, param))
+        })
+        .collect()
+}
+
+fn shared_list_name(param: &OptInput) -> Option<&'static str> {
+    SHARED_LISTS
+        .iter()
+        .find(|(suffix, _)| param.name().ends_with(suffix))
+        .map(|(_, name)| *name)
+}
+
+/// Enum a list-valued parameter is typed as, or `None` if it takes a range.
+fn list_enum_ident(indicator: &FuncInfo, idents: &FuncIdents, param: &OptInput) -> Option<Ident> {
+    param.list()?;
+
+    Some(match shared_list_name(param) {
+        Some(name) => format_ident!("{}", name),
+        None => format_ident!(
+            "{}{}",
+            indicator.camel_case_name(),
+            idents::type_name(&idents.params[param.position()])
+        ),
+    })
+}
+
+/// `self.x` for a parameter, as the value TA-Lib takes.
+fn generate_param_value(param: &OptInput, param_ident: &Ident) -> TokenStream {
+    if param.list().is_some() {
+        quote! { self.#param_ident.value() }
+    } else {
+        quote! { self.#param_ident }
+    }
+}
+
+fn generate_func_struct(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
     let indicator_ident = format_ident!("{}", indicator.camel_case_name());
     let indicator_id = indicator.name();
-    let indicator_members = generate_indicator_struct_members(indicator);
+    let indicator_members = generate_indicator_struct_members(indicator, idents);
+    let input_flags = generate_input_flags(indicator);
+    let output_names = indicator.outputs().iter().map(|output| output.name());
+    let column_suffixes = generate_column_suffixes(indicator, idents);
+    let label_func = generate_label_func(indicator, idents);
+    let history_func = generate_history_func(indicator, idents);
     let indicator_doc = format!("{}", indicator.hint());
-    let indicator_calculate_func = generate_indicator_calculate_func(indicator);
+    let prepare_func = generate_prepare_func(indicator, idents);
+    let lookback_func = generate_lookback_func(indicator);
+    let indicator_calculate_func = generate_indicator_calculate_func(indicator, idents);
+    let indicator_calculate_into_func = generate_indicator_calculate_into_func(indicator, idents);
+    let indicator_calculate_f32_func = generate_indicator_calculate_f32_func(indicator, idents);
+    let prepared_calculate_func = generate_prepared_calculate_func(indicator, idents);
+    let prepared_calculate_into_func = generate_prepared_calculate_into_func(indicator, idents);
+    let indicator_update_func = generate_indicator_update_func(indicator, idents);
+    let prepared_update_func = generate_prepared_update_func(indicator, idents);
+    let stream_func = generate_stream_func(indicator);
+    let unstable_period_funcs = generate_unstable_period_funcs(indicator);
+    let output_struct = generate_output_struct(indicator, idents);
+    let stream_push_func = generate_stream_push_func(indicator, idents);
+    let stream_ident = format_ident!("{}Stream", indicator.camel_case_name());
+    let stream_doc = format!("Streaming counterpart of [`{}`].", indicator_ident);
+    let list_enums = generate_own_list_enums(indicator, idents);
+    let (builder_attr, builder_validate_func) = generate_builder_validate_func(indicator, idents);
 
     quote! {
+        #(#list_enums)*
+
         #[doc = #indicator_doc]
         #[derive(Builder, Debug, PartialEq, Clone, Copy)]
+        #builder_attr
         pub struct #indicator_ident {
             #(#indicator_members),*
         }
 
+        #builder_validate_func
+
         impl #indicator_ident {
             const ID: &'static str = #indicator_id;
 
+            /// Price columns the indicator reads, with real inputs read from the close.
+            pub const INPUT_FLAGS: InputFlags = InputFlags::from_bits_truncate(#input_flags);
+
+            /// TA-Lib's names for the outputs, in the order they are returned.
+            pub const OUTPUT_NAMES: &'static [&'static str] = &[#(#output_names),*];
+
+            /// Suffixes naming the outputs' columns after the label, the same as the
+            /// output struct's fields. A single output is named by the label alone.
+            pub const COLUMN_SUFFIXES: &'static [&'static str] = &[#(#column_suffixes),*];
+
+            #label_func
+
+            #history_func
+
+            #prepare_func
+
+            #lookback_func
+
             #indicator_calculate_func
+
+            #indicator_calculate_into_func
+
+            #indicator_calculate_f32_func
+
+            #indicator_update_func
+
+            #stream_func
+
+            #unstable_period_funcs
+        }
+
+        impl Prepared<#indicator_ident> {
+            #prepared_calculate_func
+
+            #prepared_calculate_into_func
+
+            #prepared_update_func
+        }
+
+        #output_struct
+
+        #[doc = #stream_doc]
+        pub type #stream_ident = Stream<#indicator_ident>;
+
+        impl Stream<#indicator_ident> {
+            #stream_push_func
         }
     }
 }
 
-fn generate_indicator_struct_members(indicator: &FuncInfo) -> Vec<TokenStream> {
-    let mut params = Vec::new();
+fn generate_input_flags(indicator: &FuncInfo) -> u32 {
+    let mut flags = InputFlags::empty();
+
+    for input in indicator.inputs() {
+        match input.param_type() {
+            InputType::Price => flags |= input.flags(),
+            InputType::Real | InputType::Integer => flags |= InputFlags::CLOSE,
+        }
+    }
+
+    flags.bits()
+}
+
+fn generate_label_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let mut format = indicator.name().to_lowercase();
+    let mut periods = Vec::new();
+
+    for (param, param_ident) in indicator.params().iter().zip(generate_param_idents(idents)) {
+        if param.param_type() == OptInputType::Integer
+            && param.list().is_none()
+            && param.name().contains("Period")
+        {
+            format.push_str("_{}");
+            periods.push(quote! { self.#param_ident });
+        }
+    }
+
+    let label = if periods.is_empty() {
+        quote! { String::from(#format) }
+    } else {
+        quote! { format!(#format, #(#periods),*) }
+    };
+
+    quote! {
+        /// Name for columns holding this indicator's outputs: the function name followed
+        /// by its period parameters, like `bbands_20`.
+        pub fn label(&self) -> String {
+            #label
+        }
+    }
+}
 
-    for param in indicator.params() {
-        let member_ident = format_ident!("{}", rustify_name(param.display_name()));
+fn generate_func_test(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let indicator_ident = format_ident!("{}", indicator.camel_case_name());
+    let builder_ident = format_ident!("{}Builder", indicator.camel_case_name());
+    let test_ident = format_ident!(
+        "{}_outputs_hold_computed_values",
+        indicator.name().to_lowercase()
+    );
 
-        let member_type = match param.param_type() {
-            OptInputType::Integer => "i32",
-            OptInputType::Real => "f64",
+    let mut inputs = Vec::new();
+    for input in indicator.inputs() {
+        inputs.push(match input.param_type() {
+            InputType::Real => quote! { columns[3].as_slice() },
+            InputType::Integer => quote! {
+                columns[3].iter().map(|x| *x as i32).collect::<Vec<_>>()
+            },
+            InputType::Price => quote! {
+                &Ohlcv {
+                    open: &columns[0],
+                    high: &columns[1],
+                    low: &columns[2],
+                    close: &columns[3],
+                    volume: &columns[4],
+                    ..Ohlcv::default()
+                }
+            },
+        });
+    }
+
+    let output_lens = if indicator.outputs().len() > 1 {
+        output_field_names(idents)
+            .into_iter()
+            .map(|field| quote! { output.values().#field.len() })
+            .collect::<Vec<_>>()
+    } else {
+        vec![quote! { output.values().len() }]
+    };
+
+    let update_test_ident = format_ident!(
+        "{}_update_matches_calculate",
+        indicator.name().to_lowercase()
+    );
+    let fields = output_field_names(idents);
+    let output_checks = indicator.outputs().iter().zip(&fields).map(|(output, field)| {
+        let values = if indicator.outputs().len() > 1 {
+            quote! { values().#field }
+        } else {
+            quote! { values() }
         };
-        let member_type_ident = format_ident!("{}", member_type);
 
-        let member_default = match param.param_type() {
-            OptInputType::Integer => format!("{}", param.default() as i32),
-            OptInputType::Real => format!("{:.1}", param.default()),
+        match output.param_type() {
+            OutputType::Real => quote! {
+                assert_relative_eq!(updated.#values.as_slice(), output.#values.as_slice(), max_relative = 1e-9);
+            },
+            OutputType::Integer => quote! {
+                assert_eq!(updated.#values, output.#values);
+            },
+        }
+    });
+
+    // Miri can't call into TA-Lib, so these only run natively. The buffer handling they
+    // rely on is checked under Miri by `generate_output_test`'s tests.
+    quote! {
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn #test_ident() {
+            let columns = test_columns(300);
+            let indicator: #indicator_ident = #builder_ident::default().build().unwrap();
+            let output = indicator.calculate(#(#inputs),*).unwrap();
+
+            #(assert_eq!(#output_lens, output.len());)*
+            assert_eq!(indicator.lookback().unwrap(), output.lookback());
+            if !output.is_empty() {
+                assert_eq!(output.begin_index() + output.len(), output.input_len());
+            }
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn #update_test_ident() {
+            let indicator: #indicator_ident = #builder_ident::default().build().unwrap();
+
+            let columns = test_columns(250);
+            let previous = indicator.calculate(#(#inputs),*).unwrap();
+
+            let columns = test_columns(300);
+            let output = indicator.calculate(#(#inputs),*).unwrap();
+            let updated = indicator.update(previous, #(#inputs),*).unwrap();
+
+            assert_eq!(updated.input_range(), output.input_range());
+            #(#output_checks)*
+        }
+    }
+}
+
+/// Test running the indicator's output handling without calling TA-Lib, so Miri can
+/// check it: buffers sized from a lookback are written through pointers and trimmed to
+/// fewer values than they hold, as TA-Lib may produce, then padded back to the input's
+/// length.
+fn generate_output_test(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let test_ident = format_ident!("{}_aligns_outputs", indicator.name().to_lowercase());
+    let output_vecs = generate_output_vecs(indicator, idents);
+    let output_values = generate_output_values(indicator, idents);
+    let output_writes = indicator
+        .outputs()
+        .iter()
+        .zip(generate_output_idents(idents))
+        .map(|(output, output_ident)| {
+            let value = match output.param_type() {
+                OutputType::Real => quote! { 1.0 },
+                OutputType::Integer => quote! { 1 },
+            };
+            quote! {
+                let ptr = #output_ident.as_mut_ptr();
+                for i in 0..num_elements {
+                    unsafe { *ptr.add(i) = #value };
+                }
+            }
+        });
+
+    let (value_lens, aligned_lens) = if indicator.outputs().len() > 1 {
+        output_field_names(idents)
+            .into_iter()
+            .map(|field| {
+                (
+                    quote! { output.values().#field.len() },
+                    quote! { aligned.#field.len() },
+                )
+            })
+            .unzip()
+    } else {
+        (
+            vec![quote! { output.values().len() }],
+            vec![quote! { aligned.len() }],
+        )
+    };
+
+    quote! {
+        #[test]
+        fn #test_ident() {
+            let lookback = 3;
+            let input_len = 10;
+            let end_idx = (input_len - 1) as i32;
+            let output_size = required_output_size(lookback, 0, end_idx);
+            assert_eq!(output_size, input_len - lookback);
+
+            #(#output_vecs)*
+
+            let num_elements = output_size - 2;
+            let begin_index = input_len - num_elements;
+            #(#output_writes)*
+            let output = IndicatorOutput::new(#output_values, begin_index, num_elements, lookback, input_len);
+
+            #(assert_eq!(#value_lens, num_elements);)*
+            let aligned = output.into_aligned();
+            #(assert_eq!(#aligned_lens, input_len);)*
+        }
+    }
+}
+
+/// `X::ID` of the indicator's struct, naming the TA-Lib function in errors.
+fn generate_function_id(indicator: &FuncInfo) -> TokenStream {
+    let indicator_ident = format_ident!("{}", indicator.camel_case_name());
+    quote! { #indicator_ident::ID }
+}
+
+fn tuple_index(index: usize) -> proc_macro2::Literal {
+    proc_macro2::Literal::usize_unsuffixed(index)
+}
+
+/// Bounds TA-Lib uses for range parameters taking any real value.
+const UNBOUNDED: f64 = 1e37;
+
+/// Attribute making the builder validate range parameters in `build()`, and the
+/// validating function it calls. Both are empty if the indicator has nothing to check.
+fn generate_builder_validate_func(
+    indicator: &FuncInfo,
+    idents: &FuncIdents,
+) -> (TokenStream, TokenStream) {
+    let builder_ident = format_ident!("{}Builder", indicator.camel_case_name());
+    let mut checks = Vec::new();
+
+    for (param, param_ident) in indicator.params().iter().zip(generate_param_idents(idents)) {
+        let range = match param.range() {
+            Some(range) => range,
+            None => continue,
+        };
+
+        let bound = |value: f64| match param.param_type() {
+            OptInputType::Integer => {
+                let value = value as i32;
+                (quote! { #value }, value.to_string())
+            }
+            OptInputType::Real => (quote! { #value }, value.to_string()),
+        };
+        let (min, min_text) = bound(range.min());
+        let (max, max_text) = bound(range.max());
+
+        let (bounds, allowed) = match (range.min() > -UNBOUNDED, range.max() < UNBOUNDED) {
+            (true, true) => (
+                quote! { (#min..=#max) },
+                format!("between {} and {}", min_text, max_text),
+            ),
+            (true, false) => (quote! { (#min..) }, format!("at least {}", min_text)),
+            (false, true) => (quote! { (..=#max) }, format!("at most {}", max_text)),
+            (false, false) => continue,
+        };
+        let message = format!("{} must be {}, got {{}}", param_ident, allowed);
+        let default = default_literal(param);
+
+        checks.push(quote! {
+            let value = self.#param_ident.unwrap_or(#default);
+            if !#bounds.contains(&value) {
+                return Err(format!(#message, value));
+            }
+        });
+    }
+
+    if checks.is_empty() {
+        return (quote! {}, quote! {});
+    }
+
+    let attr = quote! { #[builder(build_fn(validate = "Self::validate"))] };
+    let func = quote! {
+        impl #builder_ident {
+            /// Checks the parameters, set or defaulted, are within the ranges TA-Lib
+            /// accepts.
+            fn validate(&self) -> Result<(), String> {
+                #(#checks)*
+
+                Ok(())
+            }
+        }
+    };
+
+    (attr, func)
+}
+
+/// TA-Lib's default for a range parameter, written out exactly.
+fn default_literal(param: &OptInput) -> proc_macro2::Literal {
+    match param.param_type() {
+        OptInputType::Integer => proc_macro2::Literal::i32_unsuffixed(param.default() as i32),
+        OptInputType::Real => proc_macro2::Literal::f64_unsuffixed(param.default()),
+    }
+}
+
+fn generate_indicator_struct_members(
+    indicator: &FuncInfo,
+    idents: &FuncIdents,
+) -> Vec<TokenStream> {
+    let mut params = Vec::new();
+
+    for (param, member_ident) in indicator.params().iter().zip(generate_param_idents(idents)) {
+        let (member_type_ident, member_default) = match list_enum_ident(indicator, idents, param) {
+            Some(enum_ident) => {
+                let default = param
+                    .list()
+                    .unwrap()
+                    .iter()
+                    .find(|value| value.value() == param.default())
+                    .unwrap_or_else(|| {
+                        panic!("{} defaults to a value not in its list", param.name())
+                    });
+                let variant = idents::type_name(default.display_name());
+                (enum_ident.clone(), format!("{}::{}", enum_ident, variant))
+            }
+            None => {
+                let member_type_ident = match param.param_type() {
+                    OptInputType::Integer => format_ident!("i32"),
+                    OptInputType::Real => format_ident!("f64"),
+                };
+                (member_type_ident, default_literal(param).to_string())
+            }
         };
 
         let member_doc = format!("{}", param.hint());
@@ -100,29 +710,202 @@ fn generate_indicator_struct_members(indicator: &FuncInfo) -> Vec<TokenStream> {
         })
     }
 
+    params.push(quote! {
+        /// What to return when the input has no more bars than the lookback
+        #[builder(default)]
+        pub short_input: ShortInputPolicy
+    });
+
     params
 }
 
-fn generate_indicator_calculate_func(indicator: &FuncInfo) -> TokenStream {
-    let (func_input_bounds, func_inputs) = generate_calculate_inputs(indicator);
-    let opt_input_params = generate_opt_input_params(indicator);
-    let input_params = generate_input_params(indicator);
-    let input_length = generate_input_length(indicator);
-    let func_outputs = generate_calculate_outputs(indicator);
-    let output_params = generate_output_params(indicator);
-    let call_and_return = generate_call_and_return(indicator);
+fn generate_prepare_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let opt_input_params = generate_opt_input_params(indicator, idents);
+    let params_mut = if opt_input_params.is_empty() {
+        quote! {}
+    } else {
+        quote! { mut }
+    };
 
     quote! {
-        pub fn calculate<#(#func_input_bounds),*>(&self, #(#func_inputs),*) -> Result<#func_outputs, Box<dyn Error>> {
-            let handle = FuncHandle::try_from(Self::ID)?;
-            let mut params = ParamHolder::try_from(handle)?;
+        /// Resolves the TA-Lib function and applies this indicator's parameters once, so
+        /// the returned `Prepared` can be calculated repeatedly without redoing that setup.
+        pub fn prepare(&self) -> Result<Prepared<Self>, IndicatorError> {
+            let handle = FuncHandle::try_from(Self::ID)
+                .map_err(|source| IndicatorError::Handle { function: Self::ID, source })?;
+            let #params_mut params = ParamHolder::try_from(handle)
+                .map_err(|source| IndicatorError::Handle { function: Self::ID, source })?;
 
             #(#opt_input_params)*
+
+            Ok(Prepared::new(*self, params))
+        }
+    }
+}
+
+fn generate_lookback_func(indicator: &FuncInfo) -> TokenStream {
+    let function = generate_function_id(indicator);
+
+    quote! {
+        /// Number of bars this configuration consumes before producing its first value,
+        /// so `lookback + 1` bars are needed for one value, without calculating anything.
+        pub fn lookback(&self) -> Result<usize, IndicatorError> {
+            self.prepare()?
+                .params
+                .lookback()
+                .ok_or(IndicatorError::OutputSize { function: #function })
+        }
+    }
+}
+
+fn generate_indicator_calculate_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let func_outputs = generate_calculate_outputs(indicator);
+    let input_idents = generate_input_idents(idents);
+    let input_length = generate_input_length(indicator, idents);
+    let direct_lookback = generate_direct_lookback(indicator, idents);
+    let bars_check = generate_bars_check(
+        indicator,
+        quote! { self },
+        generate_empty_output(indicator, idents),
+    );
+    let output_vecs = generate_output_vecs(indicator, idents);
+    let direct_call = generate_direct_call(indicator, idents, Precision::Double, quote! { 0 });
+    let output_values = generate_output_values(indicator, idents);
+
+    quote! {
+        /// Same as `calculate`, but pads the outputs to the length of the input so
+        /// bars without a computed value hold `f64::NAN` (or `INTEGER_SENTINEL`).
+        pub fn calculate_aligned<#(#func_input_bounds),*>(&self, #(#func_inputs),*) -> Result<#func_outputs, IndicatorError> {
+            Ok(self.calculate(#(#input_idents),*)?.into_aligned())
+        }
+
+        #[cfg(not(feature = "direct"))]
+        pub fn calculate<#(#func_input_bounds),*>(&self, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            self.prepare()?.calculate(#(#input_idents),*)
+        }
+
+        #[cfg(feature = "direct")]
+        pub fn calculate<#(#func_input_bounds),*>(&self, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            #input_length
+            #direct_lookback
+            #bars_check
+            let output_size = required_output_size(lookback, 0, end_idx);
+
+            #(#output_vecs)*
+
+            let (begin_index, num_elements) = #direct_call;
+            Ok(IndicatorOutput::new(#output_values, begin_index, num_elements, lookback, (end_idx + 1) as usize))
+        }
+    }
+}
+
+fn generate_indicator_calculate_into_func(
+    indicator: &FuncInfo,
+    idents: &FuncIdents,
+) -> TokenStream {
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let input_idents = generate_input_idents(idents);
+    let output_buffers = generate_output_buffers(indicator, idents);
+    let output_idents = generate_output_idents(idents);
+    let input_length = generate_input_length(indicator, idents);
+    let direct_lookback = generate_direct_lookback(indicator, idents);
+    let bars_check = generate_bars_check(indicator, quote! { self }, quote! { 0 });
+    let output_buffer_checks = generate_output_buffer_checks(indicator, idents);
+    let direct_call = generate_direct_call(indicator, idents, Precision::Double, quote! { 0 });
+
+    quote! {
+        /// Same as `calculate`, but writes the outputs into caller-provided buffers. Each
+        /// buffer must be able to hold `required_output_size` values. Returns the number of
+        /// values written to each buffer.
+        ///
+        /// The outputs aren't allocated, but TA-Lib's function handle and parameters are on
+        /// every call, as this prepares the indicator each time. Loops over many inputs should
+        /// keep the `Prepared` returned by `prepare` and call its `calculate_into`, which
+        /// doesn't allocate at all.
+        #[cfg(not(feature = "direct"))]
+        pub fn calculate_into<#(#func_input_bounds),*>(&self, #(#func_inputs),*, #(#output_buffers),*) -> Result<usize, IndicatorError> {
+            self.prepare()?.calculate_into(#(#input_idents),*, #(#output_idents),*)
+        }
+
+        /// Same as `calculate`, but writes the outputs into caller-provided buffers instead
+        /// of allocating. Each buffer must be able to hold `required_output_size` values.
+        /// Returns the number of values written to each buffer.
+        #[cfg(feature = "direct")]
+        pub fn calculate_into<#(#func_input_bounds),*>(&self, #(#func_inputs),*, #(#output_buffers),*) -> Result<usize, IndicatorError> {
+            #input_length
+            #direct_lookback
+            #bars_check
+            let output_size = required_output_size(lookback, 0, end_idx);
+
+            #(#output_buffer_checks)*
+
+            let (_begin_index, num_elements) = #direct_call;
+            Ok(num_elements)
+        }
+    }
+}
+
+fn generate_indicator_calculate_f32_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Single);
+    let func_outputs = generate_calculate_outputs(indicator);
+    let input_length = generate_input_length(indicator, idents);
+    let direct_lookback = generate_direct_lookback(indicator, idents);
+    let bars_check = generate_bars_check(
+        indicator,
+        quote! { self },
+        generate_empty_output(indicator, idents),
+    );
+    let output_vecs = generate_output_vecs(indicator, idents);
+    let direct_call = generate_direct_call(indicator, idents, Precision::Single, quote! { 0 });
+    let output_values = generate_output_values(indicator, idents);
+
+    quote! {
+        /// Same as `calculate`, but reads `f32` inputs as they are through TA-Lib's
+        /// single precision `TA_S_` functions. Outputs are still `f64`.
+        pub fn calculate_f32<#(#func_input_bounds),*>(&self, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            #input_length
+            #direct_lookback
+            #bars_check
+            let output_size = required_output_size(lookback, 0, end_idx);
+
+            #(#output_vecs)*
+
+            let (begin_index, num_elements) = #direct_call;
+            Ok(IndicatorOutput::new(#output_values, begin_index, num_elements, lookback, (end_idx + 1) as usize))
+        }
+    }
+}
+
+fn generate_prepared_calculate_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let input_params = generate_input_params(indicator, idents);
+    let input_length = generate_input_length(indicator, idents);
+    let bars_check = generate_bars_check(
+        indicator,
+        quote! { self.indicator },
+        generate_empty_output(indicator, idents),
+    );
+    let func_outputs = generate_calculate_outputs(indicator);
+    let output_params = generate_output_params(indicator, idents);
+    let call_and_return = generate_call_and_return(indicator, idents);
+
+    quote! {
+        pub fn calculate<#(#func_input_bounds),*>(&mut self, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            let params = &mut self.params;
+
             #(#input_params)*
 
             #input_length
-            let output_size = params.required_output_size(0, input_len)
-                .ok_or("Failed to get required size")?;
+            let lookback = params.lookback()
+                .ok_or(IndicatorError::OutputSize { function: #function })?;
+            #bars_check
+            let output_size = required_output_size(lookback, 0, end_idx);
 
             #(#output_params)*
 
@@ -131,12 +914,348 @@ fn generate_indicator_calculate_func(indicator: &FuncInfo) -> TokenStream {
     }
 }
 
-fn generate_opt_input_params(indicator: &FuncInfo) -> Vec<TokenStream> {
+fn generate_prepared_calculate_into_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let input_params = generate_input_params(indicator, idents);
+    let input_length = generate_input_length(indicator, idents);
+    let output_buffers = generate_output_buffers(indicator, idents);
+    let bars_check = generate_bars_check(indicator, quote! { self.indicator }, quote! { 0 });
+    let output_buffer_params = generate_output_buffer_params(indicator, idents);
+
+    quote! {
+        /// Same as `calculate`, but writes the outputs into caller-provided buffers without
+        /// allocating anything. Each buffer must be able to hold `required_output_size`
+        /// values. Returns the number of values written to each buffer.
+        pub fn calculate_into<#(#func_input_bounds),*>(&mut self, #(#func_inputs),*, #(#output_buffers),*) -> Result<usize, IndicatorError> {
+            let params = &mut self.params;
+
+            #(#input_params)*
+
+            #input_length
+            let lookback = params.lookback()
+                .ok_or(IndicatorError::OutputSize { function: #function })?;
+            #bars_check
+            let output_size = required_output_size(lookback, 0, end_idx);
+
+            #(#output_buffer_params)*
+
+            let (_begin_index, num_elements) = params.call(0, end_idx)
+                .map_err(|source| IndicatorError::Call { function: #function, source })?;
+            Ok(num_elements)
+        }
+    }
+}
+
+fn generate_indicator_update_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let func_outputs = generate_calculate_outputs(indicator);
+    let input_idents = generate_input_idents(idents);
+    let input_length = generate_input_length(indicator, idents);
+    let direct_lookback = generate_direct_lookback(indicator, idents);
+    let output_vecs = generate_output_vecs(indicator, idents);
+    let direct_call = generate_direct_call(
+        indicator,
+        idents,
+        Precision::Double,
+        quote! { start as i32 },
+    );
+    let output_values = generate_output_values(indicator, idents);
+
+    quote! {
+        /// Extends `previous`, calculated over the first bars of the input, to cover the
+        /// whole input. Only the new bars are calculated, by starting TA-Lib at the first
+        /// of them, so appending a bar to a long series doesn't redo the whole series.
+        ///
+        /// The result is the same as calculating over the whole input. Indicators whose
+        /// values depend on more bars than their lookback, like those TA-Lib flags with an
+        /// unstable period and running totals, are calculated in full to keep it that way.
+        #[cfg(not(feature = "direct"))]
+        pub fn update<#(#func_input_bounds),*>(&self, previous: IndicatorOutput<#func_outputs>, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            self.prepare()?.update(previous, #(#input_idents),*)
+        }
+
+        /// Extends `previous`, calculated over the first bars of the input, to cover the
+        /// whole input. Only the new bars are calculated, by starting TA-Lib at the first
+        /// of them, so appending a bar to a long series doesn't redo the whole series.
+        ///
+        /// The result is the same as calculating over the whole input. Indicators whose
+        /// values depend on more bars than their lookback, like those TA-Lib flags with an
+        /// unstable period and running totals, are calculated in full to keep it that way.
+        #[cfg(feature = "direct")]
+        pub fn update<#(#func_input_bounds),*>(&self, previous: IndicatorOutput<#func_outputs>, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            #input_length
+            #direct_lookback
+
+            let start = previous.input_len();
+            if self.depends_on_history() || start <= lookback || start > input_len {
+                return self.calculate(#(#input_idents),*);
+            } else if start == input_len {
+                return Ok(previous);
+            }
+            let end_idx = (input_len - 1) as i32;
+            let output_size = input_len - start;
+
+            #(#output_vecs)*
+
+            let (_begin_index, num_elements) = #direct_call;
+            Ok(previous.extend(#output_values, num_elements, input_len))
+        }
+    }
+}
+
+fn generate_prepared_update_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let (func_input_bounds, func_inputs) =
+        generate_calculate_inputs(indicator, idents, Precision::Double);
+    let func_outputs = generate_calculate_outputs(indicator);
+    let input_idents = generate_input_idents(idents);
+    let input_params = generate_input_params(indicator, idents);
+    let input_length = generate_input_length(indicator, idents);
+    let output_params = generate_output_params(indicator, idents);
+    let output_values = generate_output_values(indicator, idents);
+
+    quote! {
+        /// Extends `previous`, calculated over the first bars of the input, to cover the
+        /// whole input. See the indicator's `update` method.
+        pub fn update<#(#func_input_bounds),*>(&mut self, previous: IndicatorOutput<#func_outputs>, #(#func_inputs),*) -> Result<IndicatorOutput<#func_outputs>, IndicatorError> {
+            #input_length
+            let lookback = self.params.lookback()
+                .ok_or(IndicatorError::OutputSize { function: #function })?;
+
+            // Starting later would change the values of these, or leave no bars to warm up
+            let start = previous.input_len();
+            if self.indicator.depends_on_history() || start <= lookback || start > input_len {
+                return self.calculate(#(#input_idents),*);
+            } else if start == input_len {
+                return Ok(previous);
+            }
+            let end_idx = (input_len - 1) as i32;
+            let output_size = input_len - start;
+
+            let params = &mut self.params;
+
+            #(#input_params)*
+            #(#output_params)*
+
+            // TA-Lib warms up on the `lookback` bars before `start` by itself
+            let (_begin_index, num_elements) = params.call(start as i32, end_idx)
+                .map_err(|source| IndicatorError::Call { function: #function, source })?;
+            Ok(previous.extend(#output_values, num_elements, input_len))
+        }
+    }
+}
+
+/// `history`, the bars the indicator's values depend on with its parameters, from
+/// `rustta_bindgen`'s list and the unstable periods of its moving average types.
+fn generate_history_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let history = history::history(indicator.name());
+    let body = if history == History::Whole {
+        quote! { History::Whole }
+    } else {
+        let functions = unstable_function_ids(history);
+        let ma_types: Vec<Ident> = indicator
+            .params()
+            .iter()
+            .zip(generate_param_idents(idents))
+            .filter(|(param, _)| {
+                param.list().is_some() && shared_list_name(param) == Some("MaType")
+            })
+            .map(|(_, param_ident)| param_ident)
+            .collect();
+
+        if ma_types.is_empty() {
+            if functions.is_empty() {
+                quote! { History::Lookback }
+            } else {
+                quote! { History::Unstable(vec![#(#functions),*]) }
+            }
+        } else {
+            // Only known once the moving average types are
+            let own = if functions.is_empty() {
+                None
+            } else {
+                Some(quote! { &[#(#functions),*][..], })
+            };
+
+            quote! {
+                let unstable = [
+                    #own
+                    #(self.#ma_types.unstable_functions()),*
+                ].concat();
+
+                if unstable.is_empty() {
+                    History::Lookback
+                } else {
+                    History::Unstable(unstable)
+                }
+            }
+        }
+    };
+
+    quote! {
+        /// Bars the indicator's values depend on with its parameters.
+        pub(crate) fn history(&self) -> History {
+            #body
+        }
+
+        /// Whether values depend on more bars than the lookback with these parameters,
+        /// like those of running totals or of indicators warming up on an unstable
+        /// period, so they change with the bar a calculation starts from.
+        pub fn depends_on_history(&self) -> bool {
+            !matches!(self.history(), History::Lookback)
+        }
+    }
+}
+
+/// `set_unstable_period` and `unstable_period` for indicators TA-Lib flags with an
+/// unstable period, empty for the others.
+fn generate_unstable_period_funcs(indicator: &FuncInfo) -> TokenStream {
+    if !indicator.flags().contains(FuncFlags::UNSTABLE_PERIOD) {
+        return quote! {};
+    }
+
+    let function = generate_function_id(indicator);
+    let id = format_ident!("TA_FUNC_UNST_{}", indicator.name());
+    let set_doc = format!(
+        "Sets the unstable period of `{}`: the extra bars TA-Lib warms up on before the \
+         first value, which it adds to the lookback. It applies to every `{0}` in the \
+         process, including those other functions are built on.",
+        indicator.name()
+    );
+
+    quote! {
+        #[doc = #set_doc]
+        ///
+        /// Values start from a guess and the error it leaves shrinks with every bar after,
+        /// so the longer the period, the closer values calculated from a later bar, like
+        /// those of streams, are to calculating over the whole series. See [`Stream`] for
+        /// how close. Streams can't be created while the period is `0` and
+        /// keep the lookback they were created with, so set it before creating them.
+        ///
+        /// # Safety
+        ///
+        /// TA-Lib keeps the period in a plain global, so no other thread may call TA-Lib
+        /// while it's set.
+        pub unsafe fn set_unstable_period(period: u32) -> Result<(), IndicatorError> {
+            rustta_bindgen::unstable::set_period(direct::TA_FuncUnstId::#id, period)
+                .map_err(|source| IndicatorError::Call { function: #function, source })
+        }
+
+        /// The unstable period set with `set_unstable_period`, `0` by default.
+        pub fn unstable_period() -> u32 {
+            rustta_bindgen::unstable::period(direct::TA_FuncUnstId::#id)
+        }
+    }
+}
+
+fn generate_stream_func(indicator: &FuncInfo) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let mut real_inputs = 0usize;
+    let mut integer_inputs = 0usize;
+    let mut price_inputs = Vec::new();
+    for input in indicator.inputs() {
+        match input.param_type() {
+            InputType::Real => real_inputs += 1,
+            InputType::Integer => integer_inputs += 1,
+            InputType::Price => {
+                let flags = input.flags().bits();
+                price_inputs.push(quote! { InputFlags::from_bits_truncate(#flags) });
+            }
+        }
+    }
+
+    quote! {
+        /// Starts a stream of this indicator, to be fed one bar at a time.
+        pub fn stream(&self) -> Result<Stream<Self>, IndicatorError> {
+            let prepared = self.prepare()?;
+            let lookback = prepared.params.lookback()
+                .ok_or(IndicatorError::OutputSize { function: #function })?;
+
+            Stream::new(
+                prepared,
+                lookback,
+                #function,
+                self.history(),
+                #real_inputs,
+                #integer_inputs,
+                &[#(#price_inputs),*],
+            )
+        }
+    }
+}
+
+fn generate_stream_push_func(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let letters = 'A'..'Z';
+    let mut bounds = Vec::new();
+    let mut args = Vec::new();
+    let mut pushes = Vec::new();
+    let mut columns = Vec::new();
+    let (mut real_index, mut integer_index, mut price_index) = (0usize, 0usize, 0usize);
+
+    let input_idents = generate_input_idents(idents);
+    for ((input, input_ident), letter) in indicator.inputs().iter().zip(input_idents).zip(letters) {
+        let (arg_type, column) = match input.param_type() {
+            InputType::Real => {
+                let index = real_index;
+                real_index += 1;
+                (quote! { f64 }, quote! { self.real[#index] })
+            }
+            InputType::Integer => {
+                let index = integer_index;
+                integer_index += 1;
+                (quote! { i32 }, quote! { self.integer[#index] })
+            }
+            InputType::Price => {
+                let index = price_index;
+                price_index += 1;
+                let bound = format_ident!("{}", letter);
+                bounds.push(quote! { #bound: BarLike });
+                (quote! { #bound }, quote! { self.price[#index] })
+            }
+        };
+
+        args.push(quote! { #input_ident: #arg_type });
+        pushes.push(quote! { #column.push(#input_ident); });
+        columns.push(match input.param_type() {
+            InputType::Price => quote! { &#column },
+            _ => quote! { #column.as_slice() },
+        });
+    }
+
+    let value_type = generate_output_value_type(indicator);
+    let value_type = if indicator.outputs().len() > 1 {
+        let struct_ident = output_struct_ident(indicator);
+        quote! { #struct_ident<#value_type> }
+    } else {
+        value_type
+    };
+
+    quote! {
+        /// Pushes the next bar and returns the indicator's value for it, or `None` while
+        /// there aren't enough bars to cover the lookback.
+        pub fn push<#(#bounds),*>(&mut self, #(#args),*) -> Result<Option<#value_type>, IndicatorError> {
+            #(#pushes)*
+
+            if !self.advance() {
+                return Ok(None);
+            }
+
+            let output = self.prepared.calculate(#(#columns),*)?;
+            Ok(output.into_values().last_value())
+        }
+    }
+}
+
+fn generate_opt_input_params(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let function = generate_function_id(indicator);
     let mut params = Vec::new();
 
-    for param in indicator.params() {
+    for (param, param_ident) in indicator.params().iter().zip(generate_param_idents(idents)) {
         let position = param.position();
-        let param_ident = format_ident!("{}", rustify_name(param.display_name()));
+        let param_value = generate_param_value(param, &param_ident);
 
         let param_type_ident = match param.param_type() {
             OptInputType::Real => quote! { Real },
@@ -144,19 +1263,20 @@ fn generate_opt_input_params(indicator: &FuncInfo) -> Vec<TokenStream> {
         };
 
         params.push(quote! {
-            params.set_param(#position, OptInputParam::#param_type_ident(self.#param_ident))?;
+            params.set_param(#position, OptInputParam::#param_type_ident(#param_value))
+                .map_err(|source| IndicatorError::Param { function: #function, position: #position, source })?;
         });
     }
 
     params
 }
 
-fn generate_input_params(indicator: &FuncInfo) -> Vec<TokenStream> {
+fn generate_input_params(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let function = generate_function_id(indicator);
     let mut inputs = Vec::new();
 
-    for input in indicator.inputs() {
+    for (input, input_ident) in indicator.inputs().iter().zip(generate_input_idents(idents)) {
         let position = input.position();
-        let input_ident = format_ident!("{}", rustify_input(input.name()));
 
         let input_value = match input.param_type() {
             InputType::Integer | InputType::Real => quote! { #input_ident.as_ref().as_ptr() },
@@ -189,7 +1309,7 @@ fn generate_input_params(indicator: &FuncInfo) -> Vec<TokenStream> {
                     quote! { std::ptr::null() }
                 };
                 let open_interest = if flags.contains(InputFlags::OPEN_INTEREST) {
-                    quote! { #input_ident.open_interest() }
+                    quote! { #input_ident.open_interest().as_ptr() }
                 } else {
                     quote! { std::ptr::null()  }
                 };
@@ -214,19 +1334,24 @@ fn generate_input_params(indicator: &FuncInfo) -> Vec<TokenStream> {
         };
 
         inputs.push(quote! {
-            params.set_input(#position, InputParam::#input_type_ident(#input_value))?;
+            params.set_input(#position, InputParam::#input_type_ident(#input_value))
+                .map_err(|source| IndicatorError::Input { function: #function, position: #position, source })?;
         })
     }
 
     inputs
 }
 
-fn generate_output_params(indicator: &FuncInfo) -> Vec<TokenStream> {
+fn generate_output_params(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let function = generate_function_id(indicator);
     let mut outputs = Vec::new();
 
-    for output in indicator.outputs() {
+    for (output, output_ident) in indicator
+        .outputs()
+        .iter()
+        .zip(generate_output_idents(idents))
+    {
         let position = output.position();
-        let output_ident = format_ident!("{}", rustify_input(output.name()));
 
         let (output_type_ident, output_vec_init) = match output.param_type() {
             OutputType::Real => (quote! { Real }, quote! { 0.0 }),
@@ -234,25 +1359,87 @@ fn generate_output_params(indicator: &FuncInfo) -> Vec<TokenStream> {
         };
 
         outputs.push(quote! {
-            let #output_ident = vec![#output_vec_init; output_size];
-            let mut #output_ident = std::mem::ManuallyDrop::new(#output_ident);
+            let mut #output_ident = vec![#output_vec_init; output_size];
+            params.set_output(#position, OutputParam::#output_type_ident(#output_ident.as_mut_ptr()))
+                .map_err(|source| IndicatorError::Output { function: #function, position: #position, source })?;
+        })
+    }
+
+    outputs
+}
+
+fn generate_output_buffers(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let mut buffers = Vec::new();
+
+    for (output, output_ident) in indicator
+        .outputs()
+        .iter()
+        .zip(generate_output_idents(idents))
+    {
+        let output_type = match output.param_type() {
+            OutputType::Real => quote! { f64 },
+            OutputType::Integer => quote! { i32 },
+        };
+
+        buffers.push(quote! { #output_ident: &mut [#output_type] })
+    }
+
+    buffers
+}
+
+fn generate_output_buffer_params(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let function = generate_function_id(indicator);
+    let mut outputs = Vec::new();
+    let checks = generate_output_buffer_checks(indicator, idents);
+
+    let output_idents = generate_output_idents(idents);
+    for ((output, output_ident), check) in indicator.outputs().iter().zip(output_idents).zip(checks)
+    {
+        let position = output.position();
+
+        let output_type_ident = match output.param_type() {
+            OutputType::Real => quote! { Real },
+            OutputType::Integer => quote! { Integer },
+        };
 
-            params.set_output(#position, OutputParam::#output_type_ident(#output_ident.as_mut_ptr()))?;
+        outputs.push(quote! {
+            #check
+            params.set_output(#position, OutputParam::#output_type_ident(#output_ident.as_mut_ptr()))
+                .map_err(|source| IndicatorError::Output { function: #function, position: #position, source })?;
         })
     }
 
     outputs
 }
 
-fn generate_calculate_inputs(indicator: &FuncInfo) -> (Vec<TokenStream>, Vec<TokenStream>) {
+fn generate_output_buffer_checks(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let function = generate_function_id(indicator);
+    let mut checks = Vec::new();
+
+    for output_ident in generate_output_idents(idents) {
+        let output_name = output_ident.to_string();
+
+        checks.push(quote! {
+            check_output_buffer(#function, #output_name, #output_ident.len(), output_size)?;
+        })
+    }
+
+    checks
+}
+
+fn generate_calculate_inputs(
+    indicator: &FuncInfo,
+    idents: &FuncIdents,
+    precision: Precision,
+) -> (Vec<TokenStream>, Vec<TokenStream>) {
     let letters = 'A'..'Z';
     let mut inputs = Vec::new();
     let mut bounds = Vec::new();
 
-    for (input, letter) in indicator.inputs().iter().zip(letters) {
-        let input_ident = format_ident!("{}", rustify_input(input.name()));
+    let input_idents = generate_input_idents(idents);
+    for ((input, input_ident), letter) in indicator.inputs().iter().zip(input_idents).zip(letters) {
         let input_bound = format_ident!("{}", letter);
-        let input_type = generate_input_type(input);
+        let input_type = generate_input_type(input, precision);
 
         inputs.push(quote! { #input_ident: #input_bound });
         bounds.push(quote! { #input_bound: Length + #input_type });
@@ -261,6 +1448,176 @@ fn generate_calculate_inputs(indicator: &FuncInfo) -> (Vec<TokenStream>, Vec<Tok
     (bounds, inputs)
 }
 
+/// The identifiers generated for the indicator's names, failing the build if two of
+/// them collide.
+fn identifiers(indicator: &FuncInfo) -> FuncIdents {
+    FuncIdents::new(indicator, &idents::RESERVED).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn generate_param_idents(idents: &FuncIdents) -> Vec<Ident> {
+    idents
+        .params
+        .iter()
+        .map(|param| format_ident!("{}", param))
+        .collect()
+}
+
+fn generate_input_idents(idents: &FuncIdents) -> Vec<Ident> {
+    idents
+        .inputs
+        .iter()
+        .map(|input| format_ident!("{}", input))
+        .collect()
+}
+
+fn generate_output_idents(idents: &FuncIdents) -> Vec<Ident> {
+    idents
+        .outputs
+        .iter()
+        .map(|output| format_ident!("{}", output))
+        .collect()
+}
+
+/// `XOutput`, the struct holding the outputs of an indicator with more than one.
+fn output_struct_ident(indicator: &FuncInfo) -> Ident {
+    format_ident!("{}Output", indicator.camel_case_name())
+}
+
+/// Column suffixes for the outputs: the output struct's fields, or none for a single output.
+fn generate_column_suffixes(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<String> {
+    if indicator.outputs().len() > 1 {
+        idents.output_fields.clone()
+    } else {
+        vec![String::new()]
+    }
+}
+
+/// Field names for an indicator's outputs, like `upper_band` for `outRealUpperBand`.
+fn output_field_names(idents: &FuncIdents) -> Vec<Ident> {
+    idents
+        .output_fields
+        .iter()
+        .map(|field| format_ident!("{}", field))
+        .collect()
+}
+
+/// Type of a single value of the indicator's outputs, which TA-Lib never mixes.
+fn generate_output_value_type(indicator: &FuncInfo) -> TokenStream {
+    let output_type = indicator.outputs()[0].param_type();
+    assert!(
+        indicator
+            .outputs()
+            .iter()
+            .all(|output| output.param_type() == output_type),
+        "{} mixes real and integer outputs",
+        indicator.name()
+    );
+
+    match output_type {
+        OutputType::Real => quote! { f64 },
+        OutputType::Integer => quote! { i32 },
+    }
+}
+
+fn generate_output_struct(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    if indicator.outputs().len() < 2 {
+        return quote! {};
+    }
+
+    let indicator_ident = format_ident!("{}", indicator.camel_case_name());
+    let struct_ident = output_struct_ident(indicator);
+    let struct_doc = format!(
+        "Outputs of [`{}`], with a `Vec` per output, or a single value per output when \
+         taken bar by bar.",
+        indicator_ident
+    );
+    let fields = output_field_names(idents);
+    let first_field = &fields[0];
+    let value_type = generate_output_value_type(indicator);
+    let types: Vec<_> = fields.iter().map(|_| quote! { T }).collect();
+    let indices: Vec<_> = (0..fields.len()).map(tuple_index).collect();
+    let tuple_type = quote! { (#(#types),*) };
+    let vec_types = fields.iter().map(|_| quote! { Vec<#value_type> });
+    let vec_tuple_type = quote! { (#(#vec_types),*) };
+
+    quote! {
+        #[doc = #struct_doc]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #struct_ident<T = Vec<#value_type>> {
+            #(pub #fields: T),*
+        }
+
+        impl #struct_ident {
+            /// Iterates over the values bar by bar.
+            pub fn iter(&self) -> impl Iterator<Item = #struct_ident<#value_type>> + '_ {
+                (0..self.#first_field.len()).map(move |i| #struct_ident {
+                    #(#fields: self.#fields[i]),*
+                })
+            }
+        }
+
+        impl<T> From<#struct_ident<T>> for #tuple_type {
+            fn from(output: #struct_ident<T>) -> Self {
+                (#(output.#fields),*)
+            }
+        }
+
+        impl<T> From<#tuple_type> for #struct_ident<T> {
+            fn from(values: #tuple_type) -> Self {
+                Self {
+                    #(#fields: values.#indices),*
+                }
+            }
+        }
+
+        impl Outputs for #struct_ident {
+            fn pad(self, begin_index: usize, input_len: usize) -> Self {
+                <#vec_tuple_type>::from(self).pad(begin_index, input_len).into()
+            }
+
+            fn append(&mut self, tail: Self) {
+                #(Outputs::append(&mut self.#fields, tail.#fields);)*
+            }
+        }
+
+        impl LastValue for #struct_ident {
+            type Value = #struct_ident<#value_type>;
+
+            fn last_value(self) -> Option<Self::Value> {
+                Some(#struct_ident {
+                    #(#fields: *self.#fields.last()?),*
+                })
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl crate::array::IntoArray for #struct_ident {
+            type Array = ndarray::Array2<#value_type>;
+
+            fn into_array(self) -> Self::Array {
+                crate::array::IntoArray::into_array(<#vec_tuple_type>::from(self))
+            }
+        }
+
+        #[cfg(feature = "polars")]
+        impl crate::frame::IntoSeries for #struct_ident {
+            fn into_series(
+                self,
+                names: &[String],
+            ) -> polars::prelude::PolarsResult<Vec<polars::prelude::Series>> {
+                crate::frame::IntoSeries::into_series(<#vec_tuple_type>::from(self), names)
+            }
+        }
+
+        #[cfg(feature = "arrow")]
+        impl crate::batch::IntoArrays for #struct_ident {
+            fn into_arrays(self) -> Vec<arrow::array::ArrayRef> {
+                crate::batch::IntoArrays::into_arrays(<#vec_tuple_type>::from(self))
+            }
+        }
+    }
+}
+
 fn generate_calculate_outputs(indicator: &FuncInfo) -> TokenStream {
     let mut outputs = Vec::new();
 
@@ -270,74 +1627,278 @@ fn generate_calculate_outputs(indicator: &FuncInfo) -> TokenStream {
         outputs.push(output_type)
     }
 
-    // More than one output, wrap in tuple
+    // More than one output, named in the indicator's output struct
     if outputs.len() > 1 {
-        quote! { (#(#outputs),*) }
+        let struct_ident = output_struct_ident(indicator);
+        quote! { #struct_ident }
     } else {
         quote! { #(#outputs)* }
     }
 }
 
-fn generate_input_length(indicator: &FuncInfo) -> TokenStream {
-    let mut inputs = Vec::new();
+fn generate_input_length(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let mut columns = Vec::new();
 
-    for input in indicator.inputs() {
-        let input_ident = format_ident!("{}", rustify_input(input.name()));
-        inputs.push(input_ident);
+    // Only the columns TA-Lib reads need to line up, the rest may be left empty
+    for (input, input_ident) in indicator.inputs().iter().zip(generate_input_idents(idents)) {
+        match input.param_type() {
+            InputType::Integer | InputType::Real => {
+                let input_name = input_ident.to_string();
+                columns.push(quote! { (#input_name, #input_ident.as_ref().len()) });
+            }
+            InputType::Price => {
+                let flags = input.flags();
+                let price_columns = [
+                    (InputFlags::OPEN, "open"),
+                    (InputFlags::HIGH, "high"),
+                    (InputFlags::LOW, "low"),
+                    (InputFlags::CLOSE, "close"),
+                    (InputFlags::VOLUME, "volume"),
+                    (InputFlags::OPEN_INTEREST, "open_interest"),
+                    (InputFlags::TIMESTAMP, "timestamp"),
+                ];
+
+                for (flag, name) in price_columns.iter() {
+                    if flags.contains(*flag) {
+                        let column_ident = format_ident!("{}", name);
+                        columns.push(quote! { (#name, #input_ident.#column_ident().len()) });
+                    }
+                }
+            }
+        }
     }
 
     quote! {
-        let input_len = max!(#(#inputs.length()),*) as i32;
-        let end_idx = (min!(#(#inputs.length()),*) - 1) as i32;
+        let input_len = common_length(#function, &[#(#columns),*])?;
     }
 }
 
-fn generate_call_and_return(indicator: &FuncInfo) -> TokenStream {
-    let mut outputs = Vec::new();
+/// Returns early when the input has no more bars than the lookback, either with an
+/// error or with `empty`, depending on the indicator's `short_input` policy.
+/// Otherwise defines `end_idx`, the last input bar TA-Lib is called over.
+fn generate_bars_check(
+    indicator: &FuncInfo,
+    indicator_expr: TokenStream,
+    empty: TokenStream,
+) -> TokenStream {
+    let function = generate_function_id(indicator);
 
-    for output in indicator.outputs() {
-        let output_ident = format_ident!("{}", rustify_input(output.name()));
-        outputs.push(quote! { wrap_output(#output_ident.as_mut_ptr(), num_elements) })
+    quote! {
+        if input_len <= lookback {
+            return match #indicator_expr.short_input {
+                ShortInputPolicy::Error => Err(IndicatorError::NotEnoughBars {
+                    function: #function,
+                    required: lookback + 1,
+                    supplied: input_len,
+                }),
+                ShortInputPolicy::Empty => Ok(#empty),
+            };
+        }
+        let end_idx = (input_len - 1) as i32;
     }
+}
 
-    // More than one output wrap in tuple
-    let return_expr = if outputs.len() > 1 {
-        quote! { Ok((#(#outputs),*)) }
+fn generate_empty_output(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let values = if indicator.outputs().len() > 1 {
+        let struct_ident = output_struct_ident(indicator);
+        let fields = output_field_names(idents);
+        quote! { #struct_ident { #(#fields: Vec::new()),* } }
     } else {
-        quote! { Ok(#(#outputs)*) }
+        quote! { Vec::new() }
     };
 
+    quote! { IndicatorOutput::new(#values, input_len, 0, lookback, input_len) }
+}
+
+fn generate_output_values(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let mut outputs = Vec::new();
+
+    for output_ident in generate_output_idents(idents) {
+        outputs.push(quote! { wrap_output(#output_ident, num_elements) })
+    }
+
+    // More than one output, named in the indicator's output struct
+    if outputs.len() > 1 {
+        let struct_ident = output_struct_ident(indicator);
+        let fields = output_field_names(idents);
+        quote! { #struct_ident { #(#fields: #outputs),* } }
+    } else {
+        quote! { #(#outputs)* }
+    }
+}
+
+fn generate_call_and_return(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let function = generate_function_id(indicator);
+    let values = generate_output_values(indicator, idents);
+
+    quote! {
+        let (begin_index, num_elements) = params.call(0, end_idx)
+            .map_err(|source| IndicatorError::Call { function: #function, source })?;
+
+        // TA-Lib only reads up to `end_idx`, so that is the input the values line up with
+        Ok(IndicatorOutput::new(#values, begin_index, num_elements, lookback, (end_idx + 1) as usize))
+    }
+}
+
+fn generate_output_vecs(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let mut outputs = Vec::new();
+
+    for (output, output_ident) in indicator
+        .outputs()
+        .iter()
+        .zip(generate_output_idents(idents))
+    {
+        let output_vec_init = match output.param_type() {
+            OutputType::Real => quote! { 0.0 },
+            OutputType::Integer => quote! { 0 },
+        };
+
+        outputs.push(quote! {
+            let mut #output_ident = vec![#output_vec_init; output_size];
+        })
+    }
+
+    outputs
+}
+
+fn generate_direct_inputs(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let mut inputs = Vec::new();
+
+    for (input, input_ident) in indicator.inputs().iter().zip(generate_input_idents(idents)) {
+        match input.param_type() {
+            InputType::Integer | InputType::Real => {
+                inputs.push(quote! { #input_ident.as_ref().as_ptr() })
+            }
+            // ta_func.h takes the price columns a function reads as separate arrays, in this order
+            InputType::Price => {
+                let flags = input.flags();
+
+                if flags.contains(InputFlags::OPEN) {
+                    inputs.push(quote! { #input_ident.open().as_ptr() })
+                }
+                if flags.contains(InputFlags::HIGH) {
+                    inputs.push(quote! { #input_ident.high().as_ptr() })
+                }
+                if flags.contains(InputFlags::LOW) {
+                    inputs.push(quote! { #input_ident.low().as_ptr() })
+                }
+                if flags.contains(InputFlags::CLOSE) {
+                    inputs.push(quote! { #input_ident.close().as_ptr() })
+                }
+                if flags.contains(InputFlags::VOLUME) {
+                    inputs.push(quote! { #input_ident.volume().as_ptr() })
+                }
+                if flags.contains(InputFlags::OPEN_INTEREST) {
+                    inputs.push(quote! { #input_ident.open_interest().as_ptr() })
+                }
+                // Timestamps are only used to check lengths, TA-Lib has no argument for them
+            }
+        }
+    }
+
+    inputs
+}
+
+fn generate_direct_opt_inputs(indicator: &FuncInfo, idents: &FuncIdents) -> Vec<TokenStream> {
+    let mut params = Vec::new();
+
+    for (param, param_ident) in indicator.params().iter().zip(generate_param_idents(idents)) {
+        let param_value = generate_param_value(param, &param_ident);
+
+        // ta_func.h types every MA type parameter as the TA_MAType enum rather than an int
+        if param.name().ends_with("MAType") {
+            params.push(quote! { direct::TA_MAType(#param_value as _) })
+        } else {
+            params.push(param_value)
+        }
+    }
+
+    params
+}
+
+fn generate_direct_lookback(indicator: &FuncInfo, idents: &FuncIdents) -> TokenStream {
+    let lookback_func = format_ident!("TA_{}_Lookback", indicator.name());
+    let opt_inputs = generate_direct_opt_inputs(indicator, idents);
+
     quote! {
-        let (_begin_index, num_elements) = params.call(0, end_idx)?;
-        #return_expr
+        let lookback = unsafe { direct::#lookback_func(#(#opt_inputs),*) };
+
+        // TA-Lib reports invalid parameters with a negative lookback
+        if lookback < 0 {
+            return Err(IndicatorError::OutputSize { function: Self::ID });
+        }
+        let lookback = lookback as usize;
     }
 }
 
-fn generate_input_type(input: &Input) -> TokenStream {
+fn generate_direct_call(
+    indicator: &FuncInfo,
+    idents: &FuncIdents,
+    precision: Precision,
+    start_idx: TokenStream,
+) -> TokenStream {
+    let func = format_ident!("{}{}", precision.direct_prefix(), indicator.name());
+    let inputs = generate_direct_inputs(indicator, idents);
+    let opt_inputs = generate_direct_opt_inputs(indicator, idents);
+    let outputs = generate_output_idents(idents);
+
+    quote! {
+        {
+            let mut begin_index = 0;
+            let mut num_elements = 0;
+
+            let ret_code = unsafe {
+                direct::#func(
+                    #start_idx,
+                    end_idx,
+                    #(#inputs,)*
+                    #(#opt_inputs,)*
+                    &mut begin_index,
+                    &mut num_elements,
+                    #(#outputs.as_mut_ptr()),*
+                )
+            };
+
+            if let Some(source) = TaError::from_ret_code(ret_code) {
+                return Err(IndicatorError::Call { function: Self::ID, source });
+            }
+
+            (begin_index as usize, num_elements as usize)
+        }
+    }
+}
+
+fn generate_input_type(input: &Input, precision: Precision) -> TokenStream {
+    let real = precision.real_type();
+
     match input.param_type() {
         InputType::Integer => quote! { AsRef<[i32]> },
-        InputType::Real => quote! { AsRef<[f64]> },
+        InputType::Real => quote! { AsRef<[#real]> },
         InputType::Price => {
             let flags = input.flags();
             let mut bounds = Vec::new();
 
             if flags.contains(InputFlags::OPEN) {
-                bounds.push(quote! { Open })
+                bounds.push(quote! { Open<#real> })
             }
             if flags.contains(InputFlags::LOW) {
-                bounds.push(quote! { Low })
+                bounds.push(quote! { Low<#real> })
             }
             if flags.contains(InputFlags::HIGH) {
-                bounds.push(quote! { High })
+                bounds.push(quote! { High<#real> })
             }
             if flags.contains(InputFlags::CLOSE) {
-                bounds.push(quote! { Close })
+                bounds.push(quote! { Close<#real> })
             }
             if flags.contains(InputFlags::VOLUME) {
-                bounds.push(quote! { Volume })
+                bounds.push(quote! { Volume<#real> })
             }
             if flags.contains(InputFlags::OPEN_INTEREST) {
-                bounds.push(quote! { OpenInterest })
+                bounds.push(quote! { OpenInterest<#real> })
+            }
+            if flags.contains(InputFlags::TIMESTAMP) {
+                bounds.push(quote! { Timestamp })
             }
 
             quote! { #(#bounds)+* }
@@ -351,17 +1912,3 @@ fn generate_output_type(output: &Output) -> TokenStream {
         OutputType::Real => quote! { Vec<f64> },
     }
 }
-
-fn rustify_name(name: &str) -> String {
-    name.clone()
-        .to_lowercase()
-        .replace(" ", "_")
-        .replace("-", "_")
-}
-
-fn rustify_input(name: &str) -> String {
-    name.clone()
-        .to_lowercase()
-        .replace("in", "")
-        .replace("price", "price_")
-}
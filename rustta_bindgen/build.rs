@@ -11,6 +11,12 @@ fn main() {
         .header("wrapper.h")
         .clang_arg("-I/usr/include/ta-lib/")
         .rustified_enum(".*")
+        // Indicators store MA types as plain integers, so bind TA_MAType as a newtype
+        // that can be built from any value instead of a Rust enum
+        .newtype_enum("TA_MAType")
+        // TA-Lib returns `TA_INTERNAL_ERROR` plus an id for internal errors, which a Rust
+        // enum can't hold, so TA_RetCode is a newtype as well
+        .newtype_enum("TA_RetCode")
         .parse_callbacks(Box::new(bindgen::CargoCallbacks))
         .generate()
         .expect("Unable to generate bindings");
@@ -0,0 +1,7 @@
+//! TA-Lib's direct function interface from `ta_func.h`.
+//!
+//! Every function is available as `TA_<NAME>` for `f64` input, `TA_S_<NAME>` for `f32`
+//! input and `TA_<NAME>_Lookback`. Unlike the abstract interface these are called with
+//! plain pointers and parameters, so there's no param holder to allocate or fill in.
+
+pub use crate::ffi::*;
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate bitflags;
 
+pub mod direct;
 mod ffi;
 pub mod meta;
 pub mod types;
+pub mod unstable;
@@ -17,13 +17,14 @@ impl FuncHandle {
         let mut handle = std::ptr::null();
         let ret_code = unsafe { TA_GetFuncHandle(func.as_ptr(), &mut handle) };
 
-        match ret_code {
-            TA_RetCode::TA_SUCCESS => Ok(Self(handle)),
-            TA_RetCode::TA_FUNC_NOT_FOUND => {
-                Err(TaError::FuncNotFound(func.to_string_lossy().into_owned()))
-            }
-            _ => Err(ret_code.into()),
+        if ret_code == TA_RetCode::TA_FUNC_NOT_FOUND {
+            return Err(TaError::FuncNotFound(func.to_string_lossy().into_owned()));
         }
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
+        }
+
+        Ok(Self(handle))
     }
 }
 
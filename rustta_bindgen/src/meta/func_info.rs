@@ -6,6 +6,7 @@ use crate::{
 };
 
 use super::{
+    flags::FuncFlags,
     func_handle::FuncHandle,
     params::{input::Input, opt_input::OptInput, output::Output},
 };
@@ -17,6 +18,7 @@ pub struct FuncInfo {
     camel_case_name: String,
     hint: String,
     group: String,
+    flags: FuncFlags,
     params: Vec<OptInput>,
     inputs: Vec<Input>,
     outputs: Vec<Output>,
@@ -39,6 +41,10 @@ impl FuncInfo {
         &self.group
     }
 
+    pub fn flags(&self) -> FuncFlags {
+        self.flags
+    }
+
     pub fn params(&self) -> &[OptInput] {
         &self.params
     }
@@ -73,8 +79,8 @@ impl TryFrom<FuncHandle> for FuncInfo {
         let mut info_ptr = std::ptr::null();
         let ret_code = unsafe { TA_GetFuncInfo(*handle, &mut info_ptr) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         let name = unsafe {
@@ -101,6 +107,8 @@ impl TryFrom<FuncHandle> for FuncInfo {
                 .into_owned()
         };
 
+        let flags = unsafe { FuncFlags::from_bits_truncate((*info_ptr).flags as u32) };
+
         let param_count = unsafe { (*info_ptr).nbOptInput as usize };
         let params = Self::func_params(&handle, param_count)?;
 
@@ -116,6 +124,7 @@ impl TryFrom<FuncHandle> for FuncInfo {
             camel_case_name,
             hint,
             group,
+            flags,
             params,
             inputs,
             outputs,
@@ -129,7 +138,7 @@ mod tests {
 
     use super::*;
     use crate::meta::params::input::{InputFlags, InputType};
-    use crate::meta::params::opt_input::{OptInputFlags, OptInputType};
+    use crate::meta::params::opt_input::{ListValue, OptInputFlags, OptInputType};
     use crate::meta::params::output::{OutputFlags, OutputType};
 
     #[test]
@@ -141,6 +150,7 @@ mod tests {
         assert_eq!(info.camel_case_name(), "Bbands");
         assert_eq!(info.hint(), "Bollinger Bands");
         assert_eq!(info.group(), "Overlap Studies");
+        assert_eq!(info.flags(), FuncFlags::OVERLAP);
 
         let inputs = info.inputs();
         assert_eq!(inputs.len(), 1);
@@ -156,6 +166,7 @@ mod tests {
 
         let params = info.params();
         assert_eq!(params.len(), 4);
+        let period = params[0].range().unwrap();
         assert_eq!(
             params[0],
             OptInput::new(
@@ -167,7 +178,20 @@ mod tests {
                 String::from("Number of period"),
                 OptInputFlags::empty()
             )
+            .with_range(period)
         );
+        assert_eq!((period.min(), period.max()), (2.0, 100000.0));
+
+        let deviations = params[1].range().unwrap();
+        assert_eq!(params[3].range(), None);
+        assert!(deviations.contains(-2.0));
+        assert_eq!(deviations.precision(), 2);
+
+        let ma_types = params[3].list().unwrap();
+        assert_eq!(params[0].list(), None);
+        assert_eq!(ma_types.len(), 9);
+        assert_eq!(ma_types[0], ListValue::new(0.0, String::from("SMA")));
+        assert_eq!(ma_types[8], ListValue::new(8.0, String::from("T3")));
 
         let outputs = info.outputs();
         assert_eq!(outputs.len(), 3);
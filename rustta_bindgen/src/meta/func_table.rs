@@ -15,8 +15,8 @@ impl FuncTable {
         let mut table = std::ptr::null_mut();
         let ret_code = unsafe { TA_FuncTableAlloc(group.as_ptr(), &mut table) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(Self(table))
@@ -1,7 +1,7 @@
 use std::ffi::CStr;
 
 use crate::ffi::*;
-use crate::types::TaResult;
+use crate::types::{TaError, TaResult};
 
 pub struct GroupTable(*mut TA_StringTable);
 
@@ -10,8 +10,8 @@ impl GroupTable {
         let mut table = std::ptr::null_mut();
         let ret_code = unsafe { TA_GroupTableAlloc(&mut table) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(Self(table))
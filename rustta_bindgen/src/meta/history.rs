@@ -0,0 +1,356 @@
+use std::slice;
+
+use crate::ffi::TA_MAType;
+
+/// Bars a function's values depend on, which decides whether calculating over only the
+/// last bars gives the values a calculation over the whole series does.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum History {
+    /// Only the bars of the lookback, so calculating over the last `lookback + 1` bars
+    /// gives the value of the last one.
+    Lookback,
+    /// The lookback, which includes the unstable periods of these functions. Values
+    /// start from a guess at the first bar of the lookback and only forget it once the
+    /// periods are long enough, so with the default period of `0` they differ.
+    Unstable(&'static [&'static str]),
+    /// Every bar before them, like running totals such as `OBV`, the path dependent
+    /// `SAR` and functions returning bar indices.
+    Whole,
+}
+
+/// Functions whose values depend on every bar before them.
+static WHOLE: [&str; 8] = [
+    "AD",
+    "ADOSC",
+    "MAXINDEX",
+    "MININDEX",
+    "MINMAXINDEX",
+    "OBV",
+    "SAR",
+    "SAREXT",
+];
+
+/// Functions TA-Lib flags with an unstable period of their own.
+static OWN_UNSTABLE: [&str; 21] = [
+    "ADX",
+    "ATR",
+    "CMO",
+    "DX",
+    "EMA",
+    "HT_DCPERIOD",
+    "HT_DCPHASE",
+    "HT_PHASOR",
+    "HT_SINE",
+    "HT_TRENDLINE",
+    "HT_TRENDMODE",
+    "KAMA",
+    "MAMA",
+    "MFI",
+    "MINUS_DI",
+    "MINUS_DM",
+    "NATR",
+    "PLUS_DI",
+    "PLUS_DM",
+    "RSI",
+    "T3",
+];
+
+/// Bars a function's values depend on, with its moving average type parameters left at
+/// a type that only depends on its lookback. See [`ma_type_history`] for the others.
+///
+/// Functions built on an unstable one warm up on its period, like `MACD` on `EMA`'s
+/// and `ADXR` on `ADX`'s.
+pub fn history(name: &str) -> History {
+    if WHOLE.contains(&name) {
+        return History::Whole;
+    }
+    if let Some(own) = OWN_UNSTABLE.iter().find(|&&own| own == name) {
+        return History::Unstable(slice::from_ref(own));
+    }
+
+    match name {
+        "DEMA" | "MACD" | "MACDFIX" | "TEMA" | "TRIX" => History::Unstable(&["EMA"]),
+        "ADXR" => History::Unstable(&["ADX"]),
+        "STOCHRSI" => History::Unstable(&["RSI"]),
+        _ => History::Lookback,
+    }
+}
+
+/// Bars a moving average of the type depends on, which functions given it as a
+/// parameter depend on as well.
+pub fn ma_type_history(ma_type: TA_MAType) -> History {
+    match ma_type {
+        TA_MAType::TA_MAType_EMA | TA_MAType::TA_MAType_DEMA | TA_MAType::TA_MAType_TEMA => {
+            History::Unstable(&["EMA"])
+        }
+        TA_MAType::TA_MAType_KAMA => History::Unstable(&["KAMA"]),
+        TA_MAType::TA_MAType_MAMA => History::Unstable(&["MAMA"]),
+        TA_MAType::TA_MAType_T3 => History::Unstable(&["T3"]),
+        _ => History::Lookback,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::{TryFrom, TryInto},
+        error::Error,
+        ops::Range,
+    };
+
+    use crate::{
+        ffi::TA_FuncUnstId,
+        meta::{
+            flags::FuncFlags,
+            func_handle::FuncHandle,
+            func_info::FuncInfo,
+            params::{
+                input::InputType,
+                opt_input::OptInputType,
+                output::OutputType,
+                param_holder::{InputParam, Ohlc, OptInputParam, OutputParam, ParamHolder},
+            },
+            Meta,
+        },
+        types::TaResult,
+        unstable,
+    };
+
+    use super::*;
+
+    /// Number of the last values compared, so a single value matching by chance isn't
+    /// taken for the function not depending on history.
+    const PROBED_VALUES: usize = 10;
+
+    /// Difference allowed between values, relative to values above `1`, as running sums
+    /// are rounded differently depending on the bar they start from.
+    const TOLERANCE: f64 = 1e-9;
+
+    /// Whether calculating over only the bars each of the last values looks back on gives
+    /// different values than calculating over a generated series, with the function's
+    /// default parameters except for the integer `overrides` and the unstable periods left at `0`.
+    fn probe(info: &FuncInfo, overrides: &[(usize, i32)]) -> TaResult<bool> {
+        let mut holder = ParamHolder::try_from(FuncHandle::try_from(info.name())?)?;
+        for param in info.params() {
+            let value = match param.param_type() {
+                OptInputType::Integer => OptInputParam::Integer(param.default() as i32),
+                OptInputType::Real => OptInputParam::Real(param.default()),
+            };
+            holder.set_param(param.position(), value)?;
+        }
+        for (position, value) in overrides {
+            holder.set_param(*position, OptInputParam::Integer(*value))?;
+        }
+
+        let lookback = holder
+            .lookback()
+            .expect("no lookback with default parameters");
+        let bars = ProbeBars::new(2 * (lookback + 1) + PROBED_VALUES);
+        let whole = call(&mut holder, info, &bars, 0..bars.len())?;
+
+        for back in 0..PROBED_VALUES {
+            let end = bars.len() - back;
+            let window = call(&mut holder, info, &bars, end - lookback - 1..end)?;
+
+            for (whole, window) in whole.iter().zip(&window) {
+                let expected = whole[whole.len() - 1 - back];
+                let value = window[window.len() - 1];
+
+                let scale = value.abs().max(expected.abs()).max(1.0);
+                if (value - expected).abs() > TOLERANCE * scale {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Calls the function over `bars`, returning every output as `f64`s ending at the
+    /// last bar of `bars`.
+    fn call(
+        holder: &mut ParamHolder,
+        info: &FuncInfo,
+        bars: &ProbeBars,
+        range: Range<usize>,
+    ) -> TaResult<Vec<Vec<f64>>> {
+        let len = range.len();
+        let at = |column: &[f64]| column[range.start..].as_ptr();
+        let integer_input: Vec<i32> = bars.close[range.clone()]
+            .iter()
+            .map(|&x| x as i32)
+            .collect();
+
+        for input in info.inputs() {
+            let param = match input.param_type() {
+                InputType::Real => InputParam::Real(at(&bars.close)),
+                InputType::Integer => InputParam::Integer(integer_input.as_ptr()),
+                InputType::Price => InputParam::Ohlc(Ohlc {
+                    open: at(&bars.open),
+                    low: at(&bars.low),
+                    high: at(&bars.high),
+                    close: at(&bars.close),
+                    volume: at(&bars.volume),
+                    openinterest: at(&bars.open_interest),
+                }),
+            };
+            holder.set_input(input.position(), param)?;
+        }
+
+        let mut real_outputs = vec![vec![0.0; len]; info.outputs().len()];
+        let mut integer_outputs = vec![vec![0; len]; info.outputs().len()];
+        for output in info.outputs() {
+            let position = output.position();
+            let param = match output.param_type() {
+                OutputType::Real => OutputParam::Real(real_outputs[position].as_mut_ptr()),
+                OutputType::Integer => OutputParam::Integer(integer_outputs[position].as_mut_ptr()),
+            };
+            holder.set_output(position, param)?;
+        }
+
+        let (_begin_index, num_elements) = holder.call(0, len as i32 - 1)?;
+        assert!(num_elements > 0, "{} gave no values", info.name());
+
+        Ok(info
+            .outputs()
+            .iter()
+            .map(|output| {
+                let position = output.position();
+                match output.param_type() {
+                    OutputType::Real => real_outputs[position][..num_elements].to_vec(),
+                    OutputType::Integer => integer_outputs[position][..num_elements]
+                        .iter()
+                        .map(|&x| f64::from(x))
+                        .collect(),
+                }
+            })
+            .collect())
+    }
+
+    /// A random walk of bars, the same on every run.
+    struct ProbeBars {
+        open: Vec<f64>,
+        high: Vec<f64>,
+        low: Vec<f64>,
+        close: Vec<f64>,
+        volume: Vec<f64>,
+        open_interest: Vec<f64>,
+    }
+
+    impl ProbeBars {
+        fn new(len: usize) -> Self {
+            // Knuth's MMIX linear congruential generator
+            let mut state = 1u64;
+            let mut random = move || {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            };
+
+            let mut bars = Self {
+                open: Vec::with_capacity(len),
+                high: Vec::with_capacity(len),
+                low: Vec::with_capacity(len),
+                close: Vec::with_capacity(len),
+                volume: Vec::with_capacity(len),
+                open_interest: Vec::with_capacity(len),
+            };
+            let mut close = 100.0;
+            for _ in 0..len {
+                let open = close;
+                close += 4.0 * (random() - 0.5);
+                bars.open.push(open);
+                bars.high.push(f64::max(open, close) + random());
+                bars.low.push(f64::min(open, close) - random());
+                bars.close.push(close);
+                bars.volume.push(1000.0 + 1000.0 * random());
+                bars.open_interest.push(500.0 + 500.0 * random());
+            }
+
+            bars
+        }
+
+        fn len(&self) -> usize {
+            self.close.len()
+        }
+    }
+
+    #[test]
+    fn test_history_dependent_functions_are_listed() -> Result<(), Box<dyn Error>> {
+        let _lock = unstable::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        unsafe { unstable::set_period(TA_FuncUnstId::TA_FUNC_UNST_ALL, 0)? };
+
+        for info in Meta::new()?.data.values().flatten() {
+            if probe(info, &[])? {
+                assert_ne!(history(info.name()), History::Lookback, "{}", info.name());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_listed_functions() -> Result<(), Box<dyn Error>> {
+        let _lock = unstable::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        unsafe { unstable::set_period(TA_FuncUnstId::TA_FUNC_UNST_ALL, 0)? };
+
+        for info in Meta::new()?.data.values().flatten() {
+            let name = info.name();
+            match history(name) {
+                History::Whole => assert!(probe(info, &[])?, "{}", name),
+                History::Unstable(functions) => {
+                    for function in functions {
+                        assert!(OWN_UNSTABLE.contains(function), "{}", name);
+                    }
+                }
+                History::Lookback => {}
+            }
+
+            if info.flags().contains(FuncFlags::UNSTABLE_PERIOD) {
+                assert!(matches!(history(name), History::Unstable(_)), "{}", name);
+            }
+        }
+
+        assert_eq!(history("SMA"), History::Lookback);
+        assert_eq!(history("EMA"), History::Unstable(&["EMA"]));
+        assert_eq!(history("MACD"), History::Unstable(&["EMA"]));
+        assert_eq!(history("AD"), History::Whole);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ma_type_history() -> Result<(), Box<dyn Error>> {
+        let _lock = unstable::TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        unsafe { unstable::set_period(TA_FuncUnstId::TA_FUNC_UNST_ALL, 0)? };
+
+        let info = FuncInfo::try_from(FuncHandle::try_from("MA")?)?;
+        let param = info
+            .params()
+            .iter()
+            .find(|param| param.name() == "optInMAType")
+            .unwrap();
+
+        for value in param.list().unwrap() {
+            let ma_type = value.value() as i32;
+            let depends = probe(&info, &[(param.position(), ma_type)])?;
+
+            let history = ma_type_history(TA_MAType(ma_type.try_into()?));
+            assert_eq!(
+                depends,
+                history != History::Lookback,
+                "{}",
+                value.display_name()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,403 @@
+use std::{collections::HashSet, error::Error, fmt};
+
+use super::func_info::FuncInfo;
+
+/// Words Rust reserves, which can't be used as identifiers as they are.
+const KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "union", "unsafe", "unsized", "use", "virtual", "where", "while",
+];
+
+/// Names the generated indicator code uses for its own arguments and variables, which
+/// none of TA-Lib's names may convert to.
+pub const RESERVED: [&str; 19] = [
+    "self",
+    "short_input",
+    "bar",
+    "bars",
+    "columns",
+    "end_idx",
+    "handle",
+    "indicator",
+    "input_len",
+    "lookback",
+    "begin_index",
+    "num_elements",
+    "params",
+    "output",
+    "output_size",
+    "prepared",
+    "previous",
+    "ret_code",
+    "start",
+];
+
+/// Error returned when two of a function's names map to the same identifier.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IdentError {
+    pub function: String,
+    pub ident: String,
+}
+
+impl fmt::Display for IdentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} has more than one name converting to the identifier {}",
+            self.function, self.ident
+        )
+    }
+}
+
+impl Error for IdentError {}
+
+/// The Rust identifiers generated for a function's names, in TA-Lib's order.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FuncIdents {
+    /// Builder fields, like `time_period` for `optInTimePeriod`.
+    pub params: Vec<String>,
+    /// Input arguments, like `price_hlc` for `inPriceHLC`.
+    pub inputs: Vec<String>,
+    /// Output buffers, like `real_upper_band` for `outRealUpperBand`. Outputs that
+    /// would be named like an input keep their prefix, so `outReal` next to `inReal`
+    /// becomes `out_real`.
+    pub outputs: Vec<String>,
+    /// Fields of the named output struct, without the type, like `upper_band`.
+    pub output_fields: Vec<String>,
+}
+
+impl FuncIdents {
+    /// Converts the function's names, failing if two of them end up the same or one
+    /// is among `reserved`, the names the generated code already uses.
+    pub fn new(info: &FuncInfo, reserved: &[&str]) -> Result<Self, IdentError> {
+        let params: Vec<_> = info
+            .params()
+            .iter()
+            .map(|param| ident(strip_prefix(param.name(), "optIn")))
+            .collect();
+        let inputs: Vec<_> = info
+            .inputs()
+            .iter()
+            .map(|input| ident(strip_prefix(input.name(), "in")))
+            .collect();
+        let outputs: Vec<_> = info
+            .outputs()
+            .iter()
+            .map(|output| {
+                let name = ident(strip_prefix(output.name(), "out"));
+                if inputs.contains(&name) || params.contains(&name) {
+                    ident(output.name())
+                } else {
+                    name
+                }
+            })
+            .collect();
+        let output_fields = output_fields(info);
+
+        let mut seen: HashSet<&str> = reserved.iter().copied().collect();
+        for name in params.iter().chain(&inputs).chain(&outputs) {
+            if !seen.insert(name) {
+                return Err(IdentError {
+                    function: info.name().to_string(),
+                    ident: name.clone(),
+                });
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for name in &output_fields {
+            if !seen.insert(name) {
+                return Err(IdentError {
+                    function: info.name().to_string(),
+                    ident: name.clone(),
+                });
+            }
+        }
+
+        Ok(Self {
+            params,
+            inputs,
+            outputs,
+            output_fields,
+        })
+    }
+}
+
+/// Names the module of a function group, like `math_operators` for "Math Operators".
+pub fn module_name(group: &str) -> String {
+    ident(group)
+}
+
+/// Converts a name to an upper camel case type or variant name, like `MaType` for
+/// `ma_type` and `Sma` for "SMA".
+pub fn type_name(name: &str) -> String {
+    let name: String = snake_case_words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match name.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{}", name),
+        None => String::from("_"),
+        _ => escape_keyword(name),
+    }
+}
+
+/// Fields of the named output struct: each output without its `out` prefix and type,
+/// like `upper_band` for `outRealUpperBand`. A leading word all of them share with the
+/// function name is dropped too, so MACD's outputs become `macd`, `signal` and `hist`.
+fn output_fields(info: &FuncInfo) -> Vec<String> {
+    let words: Vec<Vec<String>> = info
+        .outputs()
+        .iter()
+        .map(|output| {
+            let name = strip_prefix(output.name(), "out");
+            let name = strip_prefix(name, "Real");
+            let name = strip_prefix(name, "Integer");
+
+            snake_case_words(name)
+        })
+        .collect();
+
+    let function = info.name().to_lowercase();
+    let shared = words.first().and_then(|w| w.first()).cloned();
+    let drop_shared = match &shared {
+        Some(shared) => {
+            function.starts_with(shared) && words.iter().all(|w| w.first() == Some(shared))
+        }
+        None => false,
+    };
+
+    words
+        .into_iter()
+        .map(|w| {
+            let w = if drop_shared && w.len() > 1 {
+                &w[1..]
+            } else {
+                &w[..]
+            };
+            escape_keyword(w.join("_"))
+        })
+        .collect()
+}
+
+/// Strips a prefix only where it ends at a word boundary, so `in` comes off `inReal`
+/// but not `integer`, and never strips the whole name.
+fn strip_prefix<'a>(name: &'a str, prefix: &str) -> &'a str {
+    match name.strip_prefix(prefix) {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_uppercase() || c == '_') => rest,
+        _ => name,
+    }
+}
+
+/// Converts a name to a snake case identifier that isn't a keyword.
+fn ident(name: &str) -> String {
+    let ident = snake_case_words(name).join("_");
+
+    match ident.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("_{}", ident),
+        None => String::from("_"),
+        _ => escape_keyword(ident),
+    }
+}
+
+/// Appends an underscore to keywords, so `type` becomes `type_`.
+fn escape_keyword(ident: String) -> String {
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident + "_"
+    } else {
+        ident
+    }
+}
+
+/// Splits a name into lowercase words at camel case boundaries and at anything that
+/// isn't a letter or digit, keeping acronyms like `MACD` whole and digits with the
+/// word before them.
+pub fn snake_case_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        if c.is_ascii_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let after_lower = prev.is_ascii_lowercase() || prev.is_ascii_digit();
+            let before_lower = matches!(chars.get(i + 1), Some(c) if c.is_ascii_lowercase());
+            if after_lower || (before_lower && prev.is_ascii_uppercase()) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c.to_ascii_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, convert::TryFrom, env, error::Error, fs};
+
+    use super::*;
+    use crate::meta::{func_handle::FuncHandle, Meta};
+
+    /// The identifiers of every function's names, with TA-Lib's name on the left and
+    /// the output struct's field in brackets. Setting `RUSTTA_UPDATE_IDENTS` rewrites
+    /// it from the linked TA-Lib instead of comparing.
+    const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/meta/idents.txt");
+
+    fn dump(info: &FuncInfo) -> Result<String, IdentError> {
+        let idents = FuncIdents::new(info, &RESERVED)?;
+        let mut lines = vec![info.name().to_string()];
+
+        for (param, ident) in info.params().iter().zip(&idents.params) {
+            lines.push(format!("    {} -> {}", param.name(), ident));
+        }
+        for (input, ident) in info.inputs().iter().zip(&idents.inputs) {
+            lines.push(format!("    {} -> {}", input.name(), ident));
+        }
+        for ((output, ident), field) in info
+            .outputs()
+            .iter()
+            .zip(&idents.outputs)
+            .zip(&idents.output_fields)
+        {
+            lines.push(format!("    {} -> {} ({})", output.name(), ident, field));
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<(), Box<dyn Error>> {
+        let meta = Meta::new()?;
+        let mut dumps = meta
+            .data
+            .values()
+            .flatten()
+            .map(|info| Ok((info.name().to_string(), dump(info)?)))
+            .collect::<Result<Vec<_>, IdentError>>()?;
+        dumps.sort();
+
+        if env::var_os("RUSTTA_UPDATE_IDENTS").is_some() {
+            let dumps: Vec<_> = dumps.into_iter().map(|(_, dump)| dump).collect();
+            fs::write(SNAPSHOT_PATH, dumps.join("\n\n") + "\n")?;
+            return Ok(());
+        }
+
+        let snapshot = fs::read_to_string(SNAPSHOT_PATH)?;
+        let expected: HashMap<&str, &str> = snapshot
+            .split("\n\n")
+            .map(|block| block.trim_end())
+            .filter_map(|block| Some((block.lines().next()?, block)))
+            .collect();
+
+        for (name, dump) in &dumps {
+            assert_eq!(
+                expected.get(name.as_str()).copied(),
+                Some(dump.as_str()),
+                "{} doesn't match {}",
+                name,
+                SNAPSHOT_PATH
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_escapes_names() {
+        assert_eq!(ident("Type"), "type_");
+        assert_eq!(ident("3Day"), "_3_day");
+        assert_eq!(type_name("ma_type"), "MaType");
+        assert_eq!(type_name("T3"), "T3");
+        assert_eq!(type_name("Self"), "Self_");
+        assert_eq!(module_name("Math Operators"), "math_operators");
+        assert_eq!(
+            module_name("Volume-Weighted (Price)"),
+            "volume_weighted_price"
+        );
+        assert_eq!(strip_prefix("outInteger", "in"), "outInteger");
+        assert_eq!(strip_prefix("inPriceHLC", "in"), "PriceHLC");
+    }
+
+    #[test]
+    fn test_func_idents() -> Result<(), Box<dyn Error>> {
+        let info = FuncInfo::try_from(FuncHandle::try_from("BBANDS")?)?;
+        let idents = FuncIdents::new(&info, &[])?;
+
+        assert_eq!(
+            idents.params,
+            vec!["time_period", "nb_dev_up", "nb_dev_dn", "ma_type"]
+        );
+        assert_eq!(idents.inputs, vec!["real"]);
+        assert_eq!(
+            idents.outputs,
+            vec!["real_upper_band", "real_middle_band", "real_lower_band"]
+        );
+        assert_eq!(
+            idents.output_fields,
+            vec!["upper_band", "middle_band", "lower_band"]
+        );
+
+        let info = FuncInfo::try_from(FuncHandle::try_from("SMA")?)?;
+        let idents = FuncIdents::new(&info, &[])?;
+        assert_eq!(idents.outputs, vec!["out_real"]);
+
+        assert_eq!(
+            FuncIdents::new(&info, &["time_period"]),
+            Err(IdentError {
+                function: String::from("SMA"),
+                ident: String::from("time_period")
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_every_function() -> Result<(), Box<dyn Error>> {
+        let meta = Meta::new()?;
+
+        for indicators in meta.data.values() {
+            for info in indicators {
+                let idents = FuncIdents::new(info, &[])?;
+                let names = idents
+                    .params
+                    .iter()
+                    .chain(&idents.inputs)
+                    .chain(&idents.outputs)
+                    .chain(&idents.output_fields);
+
+                for name in names {
+                    assert!(!KEYWORDS.contains(&name.as_str()), "{}", name);
+                    assert!(
+                        name.chars()
+                            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+                        "{} has invalid identifier {}",
+                        info.name(),
+                        name
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
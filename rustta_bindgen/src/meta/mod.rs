@@ -11,6 +11,8 @@ pub mod func_handle;
 pub mod func_info;
 pub mod func_table;
 pub mod group_table;
+pub mod history;
+pub mod idents;
 pub mod params;
 
 #[derive(Debug)]
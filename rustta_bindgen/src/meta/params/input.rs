@@ -1,7 +1,7 @@
 use std::{convert::TryFrom, ffi::CStr};
 
 use crate::{
-    ffi::{TA_GetInputParameterInfo, TA_InputParameterType, TA_RetCode},
+    ffi::{TA_GetInputParameterInfo, TA_InputParameterType},
     meta::func_handle::FuncHandle,
     types::TaError,
 };
@@ -67,8 +67,8 @@ impl TryFrom<(&FuncHandle, usize)> for Input {
         let ret_code =
             unsafe { TA_GetInputParameterInfo(**(handle.0), position as u32, &mut param_ptr) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         let name = unsafe {
@@ -1,7 +1,10 @@
 use std::{convert::TryFrom, ffi::CStr};
 
 use crate::{
-    ffi::{TA_GetOptInputParameterInfo, TA_OptInputParameterType, TA_RetCode},
+    ffi::{
+        TA_GetOptInputParameterInfo, TA_IntegerList, TA_IntegerRange, TA_OptInputParameterType,
+        TA_RealList, TA_RealRange,
+    },
     meta::func_handle::FuncHandle,
     types::TaError,
 };
@@ -23,6 +26,93 @@ impl From<TA_OptInputParameterType> for OptInputType {
     }
 }
 
+/// One of the values a list-valued parameter can take, with TA-Lib's display string
+/// for it, like `0` and `"SMA"` for the moving average types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListValue {
+    value: f64,
+    display_name: String,
+}
+
+impl ListValue {
+    pub fn new(value: f64, display_name: String) -> Self {
+        Self {
+            value,
+            display_name,
+        }
+    }
+
+    /// The value itself, which for integer lists is always a whole number.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+/// The values a range parameter accepts, with TA-Lib's suggestions for stepping
+/// through them, as when optimizing the parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamRange {
+    min: f64,
+    max: f64,
+    precision: u32,
+    suggested_start: f64,
+    suggested_end: f64,
+    suggested_step: f64,
+}
+
+impl ParamRange {
+    pub fn new(
+        min: f64,
+        max: f64,
+        precision: u32,
+        suggested_start: f64,
+        suggested_end: f64,
+        suggested_step: f64,
+    ) -> Self {
+        Self {
+            min,
+            max,
+            precision,
+            suggested_start,
+            suggested_end,
+            suggested_step,
+        }
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Number of decimals worth showing, which is 0 for integer parameters.
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    pub fn suggested_start(&self) -> f64 {
+        self.suggested_start
+    }
+
+    pub fn suggested_end(&self) -> f64 {
+        self.suggested_end
+    }
+
+    pub fn suggested_step(&self) -> f64 {
+        self.suggested_step
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OptInput {
     name: String,
@@ -32,6 +122,8 @@ pub struct OptInput {
     default: f64,
     hint: String,
     flags: OptInputFlags,
+    list: Option<Vec<ListValue>>,
+    range: Option<ParamRange>,
 }
 
 impl OptInput {
@@ -52,9 +144,24 @@ impl OptInput {
             default,
             hint,
             flags,
+            list: None,
+            range: None,
         }
     }
 
+    /// Restricts the parameter to the given range, as TA-Lib does for range parameters.
+    pub fn with_range(mut self, range: ParamRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Restricts the parameter to the given values, as TA-Lib does for list-valued
+    /// parameters.
+    pub fn with_list(mut self, list: Vec<ListValue>) -> Self {
+        self.list = Some(list);
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -82,6 +189,17 @@ impl OptInput {
     pub fn flags(&self) -> OptInputFlags {
         self.flags
     }
+
+    /// The values the parameter is restricted to, or `None` if it takes any value in a
+    /// range.
+    pub fn list(&self) -> Option<&[ListValue]> {
+        self.list.as_deref()
+    }
+
+    /// The range the parameter is restricted to, or `None` if it takes a list of values.
+    pub fn range(&self) -> Option<ParamRange> {
+        self.range
+    }
 }
 
 impl TryFrom<(&FuncHandle, usize)> for OptInput {
@@ -93,8 +211,8 @@ impl TryFrom<(&FuncHandle, usize)> for OptInput {
         let ret_code =
             unsafe { TA_GetOptInputParameterInfo(**(handle.0), position as u32, &mut param_ptr) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         let name = unsafe {
@@ -118,6 +236,8 @@ impl TryFrom<(&FuncHandle, usize)> for OptInput {
         let param_type = unsafe { OptInputType::from((*param_ptr).type_) };
         let flags = unsafe { OptInputFlags::from_bits((*param_ptr).flags as u32).unwrap() };
         let default = unsafe { (*param_ptr).defaultValue };
+        let list = unsafe { read_list((*param_ptr).type_, (*param_ptr).dataSet) };
+        let range = unsafe { read_range((*param_ptr).type_, (*param_ptr).dataSet) };
 
         Ok(Self {
             name,
@@ -127,10 +247,89 @@ impl TryFrom<(&FuncHandle, usize)> for OptInput {
             default,
             hint,
             flags,
+            list,
+            range,
         })
     }
 }
 
+/// Reads the values of a list-valued parameter from its `dataSet`, which points to a
+/// `TA_IntegerList` or `TA_RealList` depending on the parameter's type.
+///
+/// # Safety
+///
+/// `data_set` must be the `dataSet` TA-Lib gave for a parameter of type `param_type`.
+unsafe fn read_list(
+    param_type: TA_OptInputParameterType,
+    data_set: *const std::ffi::c_void,
+) -> Option<Vec<ListValue>> {
+    let read = |value: f64, string: *const std::os::raw::c_char| ListValue {
+        value,
+        display_name: CStr::from_ptr(string).to_string_lossy().into_owned(),
+    };
+
+    match param_type {
+        TA_OptInputParameterType::TA_OptInput_IntegerList => {
+            let list = &*(data_set as *const TA_IntegerList);
+            let pairs = std::slice::from_raw_parts(list.data, list.nbElement as usize);
+            Some(
+                pairs
+                    .iter()
+                    .map(|pair| read(pair.value as f64, pair.string))
+                    .collect(),
+            )
+        }
+        TA_OptInputParameterType::TA_OptInput_RealList => {
+            let list = &*(data_set as *const TA_RealList);
+            let pairs = std::slice::from_raw_parts(list.data, list.nbElement as usize);
+            Some(
+                pairs
+                    .iter()
+                    .map(|pair| read(pair.value, pair.string))
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Reads the bounds of a range parameter from its `dataSet`, which points to a
+/// `TA_IntegerRange` or `TA_RealRange` depending on the parameter's type.
+///
+/// # Safety
+///
+/// `data_set` must be the `dataSet` TA-Lib gave for a parameter of type `param_type`.
+unsafe fn read_range(
+    param_type: TA_OptInputParameterType,
+    data_set: *const std::ffi::c_void,
+) -> Option<ParamRange> {
+    match param_type {
+        TA_OptInputParameterType::TA_OptInput_IntegerRange => {
+            let range = &*(data_set as *const TA_IntegerRange);
+            Some(ParamRange::new(
+                range.min as f64,
+                range.max as f64,
+                0,
+                range.suggested_start as f64,
+                range.suggested_end as f64,
+                range.suggested_increment as f64,
+            ))
+        }
+        TA_OptInputParameterType::TA_OptInput_RealRange => {
+            let range = &*(data_set as *const TA_RealRange);
+            Some(ParamRange::new(
+                range.min,
+                range.max,
+                range.precision as u32,
+                range.suggested_start,
+                range.suggested_end,
+                range.suggested_increment,
+            ))
+        }
+        _ => None,
+    }
+}
+
 bitflags! {
     #[derive(Default)]
     pub struct OptInputFlags: u32 {
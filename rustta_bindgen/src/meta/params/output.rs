@@ -1,7 +1,7 @@
 use std::{convert::TryFrom, ffi::CStr};
 
 use crate::{
-    ffi::{TA_GetOutputParameterInfo, TA_OutputParameterType, TA_RetCode},
+    ffi::{TA_GetOutputParameterInfo, TA_OutputParameterType},
     meta::func_handle::FuncHandle,
     types::TaError,
 };
@@ -65,8 +65,8 @@ impl TryFrom<(&FuncHandle, usize)> for Output {
         let ret_code =
             unsafe { TA_GetOutputParameterInfo(**(handle.0), position as u32, &mut param_ptr) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         let name = unsafe {
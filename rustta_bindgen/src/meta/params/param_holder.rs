@@ -54,8 +54,8 @@ impl ParamHolder {
             },
         };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(TaError::BadParam);
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(())
@@ -71,8 +71,8 @@ impl ParamHolder {
             OptInputParam::Integer(x) => unsafe { TA_SetOptInputParamInteger(self.0, position, x) },
         };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(TaError::BadParam);
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(())
@@ -88,8 +88,8 @@ impl ParamHolder {
             },
         };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(TaError::BadParam);
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(())
@@ -100,26 +100,18 @@ impl ParamHolder {
         let ret_code = unsafe { TA_GetLookback(self.0, lookback_ptr) };
 
         match ret_code {
+            // Invalid parameters are reported with a negative lookback rather than an error
             TA_RetCode::TA_SUCCESS => {
                 let lookback = unsafe { *lookback_ptr };
-                Some(lookback as usize)
+                usize::try_from(lookback).ok()
             }
             _ => None,
         }
     }
 
     pub fn required_output_size(&self, start: i32, end: i32) -> Option<usize> {
-        let start = start as usize;
-        let end = end as usize;
-
         let lookback = self.lookback()?;
-        let temp = max(lookback, start);
-
-        if temp > end {
-            Some(0)
-        } else {
-            Some(end - temp + 1)
-        }
+        Some(required_output_size(lookback, start, end))
     }
 
     pub fn call(&self, start: i32, end: i32) -> TaResult<(usize, usize)> {
@@ -128,15 +120,14 @@ impl ParamHolder {
 
         let ret_code = unsafe { TA_CallFunc(self.0, start, end, begin_idx_ptr, num_elements_ptr) };
 
-        match ret_code {
-            TA_RetCode::TA_SUCCESS => {
-                let begin_idx = unsafe { *begin_idx_ptr } as usize;
-                let num_elements = unsafe { *num_elements_ptr } as usize;
-
-                Ok((begin_idx, num_elements))
-            }
-            _ => Err(TaError::Misc("Error calling func".into())),
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
+
+        let begin_idx = unsafe { *begin_idx_ptr } as usize;
+        let num_elements = unsafe { *num_elements_ptr } as usize;
+
+        Ok((begin_idx, num_elements))
     }
 }
 
@@ -147,8 +138,8 @@ impl TryFrom<FuncHandle> for ParamHolder {
         let mut holder = std::ptr::null_mut();
         let ret_code = unsafe { TA_ParamHolderAlloc(*handle, &mut holder) };
 
-        if ret_code != TA_RetCode::TA_SUCCESS {
-            return Err(ret_code.into());
+        if let Some(error) = TaError::from_ret_code(ret_code) {
+            return Err(error);
         }
 
         Ok(Self(holder))
@@ -161,14 +152,36 @@ impl Drop for ParamHolder {
     }
 }
 
-pub fn wrap_output<T>(ptr: *mut T, num_elements: usize) -> Vec<T> {
-    let output = unsafe { Vec::from_raw_parts(ptr, num_elements, num_elements) };
+/// Number of values a function with the given lookback produces when called over `start..=end`.
+pub fn required_output_size(lookback: usize, start: i32, end: i32) -> usize {
+    let start = start as usize;
+    let end = end as usize;
+
+    let temp = max(lookback, start);
+
+    if temp > end {
+        0
+    } else {
+        end - temp + 1
+    }
+}
+
+/// Trims an output buffer that TA-Lib wrote into down to the `num_elements` values it produced.
+pub fn wrap_output<T>(mut output: Vec<T>, num_elements: usize) -> Vec<T> {
+    assert!(
+        num_elements <= output.len(),
+        "TA-Lib reported {} values for a buffer of {}",
+        num_elements,
+        output.len()
+    );
+
+    output.truncate(num_elements);
     output
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{convert::TryInto, error::Error};
+    use std::{convert::TryInto, error::Error, rc::Rc};
 
     use super::*;
 
@@ -239,9 +252,69 @@ mod tests {
         param_holder.set_param(0, OptInputParam::Integer(5))?;
         assert_eq!(param_holder.lookback().unwrap(), 4);
 
+        param_holder.set_param(0, OptInputParam::Integer(1))?;
+        assert_eq!(param_holder.lookback(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_reports_ret_code() -> Result<(), Box<dyn Error>> {
+        let handle = FuncHandle::try_from("SMA")?;
+        let mut param_holder = ParamHolder::try_from(handle)?;
+
+        // Output isn't set yet
+        let data = [1.0, 2.0, 3.0];
+        param_holder.set_input(0, InputParam::Real(data.as_ptr()))?;
+        assert!(matches!(
+            param_holder.call(0, 2),
+            Err(TaError::OutputNotAllInitialized(_))
+        ));
+
+        let mut out = [0.0; 3];
+        param_holder.set_output(0, OutputParam::Real(out.as_mut_ptr()))?;
+        assert!(matches!(
+            param_holder.call(2, 1),
+            Err(TaError::OutOfRangeEndIndex(_))
+        ));
+
         Ok(())
     }
 
+    #[test]
+    fn test_wrap_output_trims_to_num_elements() {
+        let output = wrap_output(vec![1.0, 2.0, 0.0, 0.0], 2);
+        assert_eq!(output, vec![1.0, 2.0]);
+
+        let output = wrap_output(vec![1, 2, 3], 3);
+        assert_eq!(output, vec![1, 2, 3]);
+
+        let output: Vec<f64> = wrap_output(vec![0.0; 3], 0);
+        assert!(output.is_empty());
+    }
+
+    // Doesn't touch TA-Lib, so unlike the tests calling into it this one runs under Miri
+    #[test]
+    fn test_wrap_output_keeps_buffer_and_drops_trimmed_values() {
+        let value = Rc::new(1.0);
+        let buffer = vec![Rc::clone(&value); 4];
+        let ptr = buffer.as_ptr();
+
+        let output = wrap_output(buffer, 1);
+        assert_eq!(output.as_ptr(), ptr);
+        assert_eq!(output.len(), 1);
+        assert_eq!(Rc::strong_count(&value), 2);
+
+        drop(output);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrap_output_rejects_overlong_count() {
+        wrap_output(vec![0.0; 2], 3);
+    }
+
     #[test]
     fn test_required_output_size() -> Result<(), Box<dyn Error>> {
         let handle = FuncHandle::try_from("SMA")?;
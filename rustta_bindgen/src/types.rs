@@ -1,13 +1,35 @@
 use super::ffi::*;
-use std::{error::Error, fmt};
+use std::{error::Error, ffi::CStr, fmt};
 
 pub type TaResult<T> = Result<T, TaError>;
 
+/// Errors returned by TA-Lib. Variants created from a `TA_RetCode` carry
+/// TA-Lib's own description of the code.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TaError {
-    BadAllocation,
-    BadParam,
+    LibNotInitialized(String),
+    BadParam(String),
+    BadAllocation(String),
+    GroupNotFound(String),
+    /// Holds the name of the function that couldn't be found, or TA-Lib's description
+    /// when the name isn't known.
     FuncNotFound(String),
+    InvalidHandle(String),
+    InvalidParamHolder(String),
+    InvalidParamHolderType(String),
+    InvalidParamFunction(String),
+    InputNotAllInitialized(String),
+    OutputNotAllInitialized(String),
+    OutOfRangeStartIndex(String),
+    OutOfRangeEndIndex(String),
+    InvalidListType(String),
+    BadObject(String),
+    NotSupported(String),
+    /// Holds the id TA-Lib adds to `TA_INTERNAL_ERROR` to tell where it failed.
+    InternalError(u32, String),
+    UnknownError(String),
+    /// Holds a return code TA-Lib doesn't define and TA-Lib's description of it.
+    Unknown(u32, String),
     Misc(String),
     NotEnoughBars,
 }
@@ -15,9 +37,25 @@ pub enum TaError {
 impl fmt::Display for TaError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            TaError::BadAllocation => write!(f, "Bad allocation"),
-            TaError::BadParam => write!(f, "Bad param"),
-            TaError::FuncNotFound(e) => write!(f, "Function with name {} not found", e),
+            TaError::LibNotInitialized(e) => write!(f, "Library not initialized: {}", e),
+            TaError::BadParam(e) => write!(f, "Bad param: {}", e),
+            TaError::BadAllocation(e) => write!(f, "Bad allocation: {}", e),
+            TaError::GroupNotFound(e) => write!(f, "Group not found: {}", e),
+            TaError::FuncNotFound(e) => write!(f, "Function not found: {}", e),
+            TaError::InvalidHandle(e) => write!(f, "Invalid handle: {}", e),
+            TaError::InvalidParamHolder(e) => write!(f, "Invalid param holder: {}", e),
+            TaError::InvalidParamHolderType(e) => write!(f, "Invalid param holder type: {}", e),
+            TaError::InvalidParamFunction(e) => write!(f, "Invalid param function: {}", e),
+            TaError::InputNotAllInitialized(e) => write!(f, "Inputs not all set: {}", e),
+            TaError::OutputNotAllInitialized(e) => write!(f, "Outputs not all set: {}", e),
+            TaError::OutOfRangeStartIndex(e) => write!(f, "Start index out of range: {}", e),
+            TaError::OutOfRangeEndIndex(e) => write!(f, "End index out of range: {}", e),
+            TaError::InvalidListType(e) => write!(f, "Invalid list type: {}", e),
+            TaError::BadObject(e) => write!(f, "Bad object: {}", e),
+            TaError::NotSupported(e) => write!(f, "Not supported: {}", e),
+            TaError::InternalError(id, e) => write!(f, "Internal error {}: {}", id, e),
+            TaError::UnknownError(e) => write!(f, "Unknown error: {}", e),
+            TaError::Unknown(code, e) => write!(f, "Unrecognized return code {}: {}", code, e),
             TaError::Misc(e) => write!(f, "Misc error: {}", e),
             TaError::NotEnoughBars => write!(f, "Not enough bars for given start and end"),
         }
@@ -26,12 +64,110 @@ impl fmt::Display for TaError {
 
 impl Error for TaError {}
 
-impl From<TA_RetCode> for TaError {
-    fn from(code: TA_RetCode) -> Self {
-        match code {
-            TA_RetCode::TA_ALLOC_ERR => Self::BadAllocation,
-            TA_RetCode::TA_BAD_PARAM => Self::BadParam,
-            code => Self::Misc(format!("Unknown return code: {:?}", code)),
-        }
+/// Codes TA-Lib reports internal errors with, `TA_INTERNAL_ERROR` plus an id.
+const INTERNAL_ERROR_FIRST: u32 = TA_RetCode::TA_INTERNAL_ERROR.0;
+const INTERNAL_ERROR_LAST: u32 = INTERNAL_ERROR_FIRST + 999;
+
+impl TaError {
+    /// The error a return code stands for, or `None` for `TA_SUCCESS`.
+    pub fn from_ret_code(code: TA_RetCode) -> Option<Self> {
+        let info = || ret_code_info(code);
+
+        let error = match code {
+            TA_RetCode::TA_SUCCESS => return None,
+            TA_RetCode::TA_LIB_NOT_INITIALIZE => Self::LibNotInitialized(info()),
+            TA_RetCode::TA_BAD_PARAM => Self::BadParam(info()),
+            TA_RetCode::TA_ALLOC_ERR => Self::BadAllocation(info()),
+            TA_RetCode::TA_GROUP_NOT_FOUND => Self::GroupNotFound(info()),
+            TA_RetCode::TA_FUNC_NOT_FOUND => Self::FuncNotFound(info()),
+            TA_RetCode::TA_INVALID_HANDLE => Self::InvalidHandle(info()),
+            TA_RetCode::TA_INVALID_PARAM_HOLDER => Self::InvalidParamHolder(info()),
+            TA_RetCode::TA_INVALID_PARAM_HOLDER_TYPE => Self::InvalidParamHolderType(info()),
+            TA_RetCode::TA_INVALID_PARAM_FUNCTION => Self::InvalidParamFunction(info()),
+            TA_RetCode::TA_INPUT_NOT_ALL_INITIALIZE => Self::InputNotAllInitialized(info()),
+            TA_RetCode::TA_OUTPUT_NOT_ALL_INITIALIZE => Self::OutputNotAllInitialized(info()),
+            TA_RetCode::TA_OUT_OF_RANGE_START_INDEX => Self::OutOfRangeStartIndex(info()),
+            TA_RetCode::TA_OUT_OF_RANGE_END_INDEX => Self::OutOfRangeEndIndex(info()),
+            TA_RetCode::TA_INVALID_LIST_TYPE => Self::InvalidListType(info()),
+            TA_RetCode::TA_BAD_OBJECT => Self::BadObject(info()),
+            TA_RetCode::TA_NOT_SUPPORTED => Self::NotSupported(info()),
+            TA_RetCode::TA_UNKNOWN_ERR => Self::UnknownError(info()),
+            TA_RetCode(code @ INTERNAL_ERROR_FIRST..=INTERNAL_ERROR_LAST) => {
+                Self::InternalError(code - INTERNAL_ERROR_FIRST, info())
+            }
+            TA_RetCode(code) => Self::Unknown(code, info()),
+        };
+
+        Some(error)
+    }
+}
+
+/// TA-Lib's description of a return code, as filled in by `TA_SetRetCodeInfo`.
+fn ret_code_info(code: TA_RetCode) -> String {
+    let mut info = TA_RetCodeInfo {
+        enumStr: std::ptr::null(),
+        infoStr: std::ptr::null(),
+    };
+    unsafe { TA_SetRetCodeInfo(code, &mut info) };
+
+    if info.infoStr.is_null() {
+        return format!("{:?}", code);
+    }
+
+    unsafe { CStr::from_ptr(info.infoStr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ret_code() {
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode::TA_BAD_PARAM),
+            Some(TaError::BadParam("A parameter is out of range".into()))
+        );
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode::TA_OUT_OF_RANGE_END_INDEX),
+            Some(TaError::OutOfRangeEndIndex("End index out of range".into()))
+        );
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode::TA_FUNC_NOT_FOUND),
+            Some(TaError::FuncNotFound("No such function".into()))
+        );
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode::TA_UNKNOWN_ERR),
+            Some(TaError::UnknownError("Unknown Error".into()))
+        );
+    }
+
+    #[test]
+    fn test_success_is_not_an_error() {
+        assert_eq!(TaError::from_ret_code(TA_RetCode::TA_SUCCESS), None);
+    }
+
+    #[test]
+    fn test_internal_error_keeps_id() {
+        let info = "Unexpected Internal Error - Contact TA-Lib.org";
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode::TA_INTERNAL_ERROR),
+            Some(TaError::InternalError(0, info.into()))
+        );
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode(5042)),
+            Some(TaError::InternalError(42, info.into()))
+        );
+        assert_eq!(
+            TaError::from_ret_code(TA_RetCode(6000)),
+            Some(TaError::Unknown(6000, "Unknown Error".into()))
+        );
+    }
+
+    #[test]
+    fn test_display_includes_info() {
+        let error = TaError::from_ret_code(TA_RetCode::TA_BAD_PARAM).unwrap();
+        assert_eq!(error.to_string(), "Bad param: A parameter is out of range");
     }
 }
@@ -0,0 +1,81 @@
+//! TA-Lib's unstable periods: extra bars functions flagged `UNSTABLE_PERIOD` warm up
+//! on before their first value, on top of the bars their parameters need.
+//!
+//! TA-Lib keeps one period per function for the whole process, and includes it in the
+//! function's lookback. Functions built on another one share its period, like `MACD`
+//! using `EMA`'s.
+
+use crate::{
+    ffi::*,
+    types::{TaError, TaResult},
+};
+
+/// Sets the unstable period of a function, or of every function for
+/// `TA_FUNC_UNST_ALL`.
+///
+/// # Safety
+///
+/// TA-Lib stores the period in a plain global, so no other thread may call TA-Lib while
+/// it's set. Lookbacks already read with the old period no longer match what TA-Lib
+/// produces, so buffers sized from them may be too small.
+pub unsafe fn set_period(id: TA_FuncUnstId, period: u32) -> TaResult<()> {
+    // TA-Lib only checks ids against `TA_FUNC_UNST_ALL`, so it would write before its
+    // table for `TA_FUNC_UNST_NONE`
+    if id == TA_FuncUnstId::TA_FUNC_UNST_NONE {
+        return Err(TaError::BadParam(
+            "TA_FUNC_UNST_NONE has no unstable period".into(),
+        ));
+    }
+
+    let ret_code = TA_SetUnstablePeriod(id, period);
+
+    if let Some(error) = TaError::from_ret_code(ret_code) {
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// The unstable period of a function, `0` unless it was set. `TA_FUNC_UNST_ALL` and
+/// `TA_FUNC_UNST_NONE` have none of their own.
+pub fn period(id: TA_FuncUnstId) -> u32 {
+    if id == TA_FuncUnstId::TA_FUNC_UNST_NONE {
+        return 0;
+    }
+
+    unsafe { TA_GetUnstablePeriod(id) }
+}
+
+/// Serializes the tests changing unstable periods, as they are shared by the whole test
+/// binary. Only meant for tests, including those of crates built on this one.
+#[doc(hidden)]
+pub static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_set_period() -> Result<(), Box<dyn Error>> {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        unsafe { set_period(TA_FuncUnstId::TA_FUNC_UNST_EMA, 7)? };
+        assert_eq!(period(TA_FuncUnstId::TA_FUNC_UNST_EMA), 7);
+        assert_eq!(period(TA_FuncUnstId::TA_FUNC_UNST_RSI), 0);
+
+        unsafe { set_period(TA_FuncUnstId::TA_FUNC_UNST_ALL, 0)? };
+        assert_eq!(period(TA_FuncUnstId::TA_FUNC_UNST_EMA), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_none_is_rejected() {
+        let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let result = unsafe { set_period(TA_FuncUnstId::TA_FUNC_UNST_NONE, 7) };
+        assert!(matches!(result, Err(TaError::BadParam(_))));
+    }
+}
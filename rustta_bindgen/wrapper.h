@@ -1,2 +1,3 @@
 #include "ta_common.h"
-#include "ta_abstract.h"
\ No newline at end of file
+#include "ta_abstract.h"
+#include "ta_func.h"
\ No newline at end of file
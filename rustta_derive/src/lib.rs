@@ -0,0 +1,127 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Field, Fields, GenericParam,
+    Ident, Lifetime, LifetimeDef,
+};
+
+/// Columns a field can be marked as, with the input trait that reads it.
+const COLUMNS: [(&str, &str); 7] = [
+    ("open", "Open"),
+    ("high", "High"),
+    ("low", "Low"),
+    ("close", "Close"),
+    ("volume", "Volume"),
+    ("open_interest", "OpenInterest"),
+    ("timestamp", "Timestamp"),
+];
+
+/// Implements `Length` and the input traits of the marked columns on references to
+/// a struct, so it can be passed to price-input indicators like `Ohlcv` can.
+///
+/// Each column field is marked with `#[rustta(<column>)]`, where the column is one of
+/// `open`, `high`, `low`, `close`, `volume`, `open_interest` or `timestamp`. Price
+/// fields must implement `AsRef<[f64]>` and the timestamp field `AsRef<[i64]>`.
+#[proc_macro_derive(RusttaInput, attributes(rustta))]
+pub fn derive_rustta_input(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "expected named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "expected a struct")),
+    };
+
+    let mut columns: Vec<(Ident, &Field)> = Vec::new();
+    for field in fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("rustta"))
+        {
+            let column: Ident = attr.parse_args()?;
+
+            if !COLUMNS.iter().any(|(name, _)| column == name) {
+                return Err(Error::new_spanned(
+                    &column,
+                    "expected one of open, high, low, close, volume, open_interest or timestamp",
+                ));
+            }
+            if columns.iter().any(|(other, _)| *other == column) {
+                return Err(Error::new_spanned(
+                    &column,
+                    format!("more than one field is marked as {}", column),
+                ));
+            }
+
+            columns.push((column, field));
+        }
+    }
+
+    if columns.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "mark at least one field with #[rustta(<column>)]",
+        ));
+    }
+
+    // The input traits are implemented on references, like they are for `Ohlcv`
+    let mut generics = input.generics.clone();
+    let lifetime = Lifetime::new("'__rustta", Span::call_site());
+    generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = &input.ident;
+    let self_ty = quote! { &#lifetime #name #ty_generics };
+
+    let mut lengths = Vec::new();
+    let mut impls = Vec::new();
+    for (column, field) in &columns {
+        let trait_name = COLUMNS.iter().find(|(name, _)| column == name).unwrap().1;
+        let trait_ident = format_ident!("{}", trait_name);
+        let item = if column == "timestamp" {
+            quote! { i64 }
+        } else {
+            quote! { f64 }
+        };
+        let field_ident = &field.ident;
+        // Spanned to the field's type so a type that can't be read as a column is
+        // reported on the field rather than on the derive
+        let value = quote_spanned! {field.ty.span()=>
+            ::std::convert::AsRef::<[#item]>::as_ref(&self.#field_ident)
+        };
+
+        lengths.push(quote! { #value.len() });
+        impls.push(quote! {
+            impl #impl_generics ::rustta::input::#trait_ident for #self_ty #where_clause {
+                fn #column(&self) -> &[#item] {
+                    #value
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::rustta::input::Length for #self_ty #where_clause {
+            fn length(&self) -> usize {
+                let mut length = 0;
+                #(length = ::std::cmp::max(length, #lengths);)*
+                length
+            }
+        }
+
+        #(#impls)*
+    })
+}
@@ -0,0 +1,5 @@
+#[test]
+fn rejects_invalid_inputs() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,22 @@
+extern crate self as rustta;
+
+// Stands in for the traits the derive implements from `rustta::input`
+mod input {
+    pub trait Length {
+        fn length(&self) -> usize;
+    }
+    pub trait High {
+        fn high(&self) -> &[f64];
+    }
+    pub trait Close {
+        fn close(&self) -> &[f64];
+    }
+}
+
+#[derive(rustta_derive::RusttaInput)]
+struct Candles {
+    high: Vec<f64>,
+    close: Vec<f64>,
+}
+
+fn main() {}
@@ -0,0 +1,242 @@
+use std::{borrow::Cow, error::Error, fmt};
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+
+use crate::{
+    input::{Close, High, Length, Low, Open, Volume},
+    output::IndicatorOutput,
+};
+
+/// Error returned when an array can't be used as indicator input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArrayError {
+    /// The view isn't laid out contiguously, so it can't be borrowed as a slice.
+    /// Use the `copied` constructors to copy it instead.
+    NotContiguous,
+    /// An OHLCV array needs 4 (open, high, low, close) or 5 (plus volume) columns.
+    ColumnCount(usize),
+}
+
+impl fmt::Display for ArrayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArrayError::NotContiguous => write!(f, "Array view isn't contiguous"),
+            ArrayError::ColumnCount(n) => {
+                write!(f, "Expected 4 or 5 OHLCV columns but got {}", n)
+            }
+        }
+    }
+}
+
+impl Error for ArrayError {}
+
+fn borrow_or_copy(view: ArrayView1<'_, f64>, copy: bool) -> Result<Cow<'_, [f64]>, ArrayError> {
+    match view.to_slice() {
+        Some(slice) => Ok(Cow::Borrowed(slice)),
+        None if copy => Ok(Cow::Owned(view.to_vec())),
+        None => Err(ArrayError::NotContiguous),
+    }
+}
+
+/// A one-dimensional array passed as a real input.
+///
+/// This is the only way to pass ndarray input: real inputs are read through
+/// `AsRef<[f64]>`, which can't be implemented for `ArrayView1` outside ndarray.
+#[derive(Debug, Clone)]
+pub struct ArrayColumn<'a>(Cow<'a, [f64]>);
+
+impl<'a> ArrayColumn<'a> {
+    /// Borrows a contiguous view, rejecting any other.
+    pub fn new(view: ArrayView1<'a, f64>) -> Result<Self, ArrayError> {
+        borrow_or_copy(view, false).map(Self)
+    }
+
+    /// Borrows a contiguous view, or copies it if it isn't contiguous.
+    pub fn copied(view: ArrayView1<'a, f64>) -> Self {
+        Self(borrow_or_copy(view, true).unwrap())
+    }
+}
+
+impl<'a> AsRef<[f64]> for ArrayColumn<'a> {
+    fn as_ref(&self) -> &[f64] {
+        &self.0
+    }
+}
+
+impl<'a> Length for ArrayColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> Length for &ArrayColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// The columns of a two-dimensional array passed as price input, in the order open,
+/// high, low, close and, optionally, volume.
+///
+/// Columns are only contiguous in column-major (Fortran order) arrays, so views in
+/// the default row-major order have to be copied.
+#[derive(Debug, Clone)]
+pub struct OhlcvArray<'a> {
+    columns: Vec<Cow<'a, [f64]>>,
+}
+
+impl<'a> OhlcvArray<'a> {
+    /// Borrows the columns of the view, rejecting it if they aren't contiguous.
+    pub fn new(view: ArrayView2<'a, f64>) -> Result<Self, ArrayError> {
+        Self::from_view(view, false)
+    }
+
+    /// Borrows the columns of the view, copying those that aren't contiguous.
+    pub fn copied(view: ArrayView2<'a, f64>) -> Result<Self, ArrayError> {
+        Self::from_view(view, true)
+    }
+
+    fn from_view(view: ArrayView2<'a, f64>, copy: bool) -> Result<Self, ArrayError> {
+        let ncols = view.ncols();
+        if ncols != 4 && ncols != 5 {
+            return Err(ArrayError::ColumnCount(ncols));
+        }
+
+        let columns = (0..ncols)
+            .map(|j| borrow_or_copy(view.index_axis_move(Axis(1), j), copy))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { columns })
+    }
+}
+
+impl<'a> Length for &OhlcvArray<'a> {
+    fn length(&self) -> usize {
+        self.columns[0].len()
+    }
+}
+
+impl<'a> Open for &OhlcvArray<'a> {
+    fn open(&self) -> &[f64] {
+        &self.columns[0]
+    }
+}
+impl<'a> High for &OhlcvArray<'a> {
+    fn high(&self) -> &[f64] {
+        &self.columns[1]
+    }
+}
+impl<'a> Low for &OhlcvArray<'a> {
+    fn low(&self) -> &[f64] {
+        &self.columns[2]
+    }
+}
+impl<'a> Close for &OhlcvArray<'a> {
+    fn close(&self) -> &[f64] {
+        &self.columns[3]
+    }
+}
+impl<'a> Volume for &OhlcvArray<'a> {
+    fn volume(&self) -> &[f64] {
+        match self.columns.get(4) {
+            Some(volume) => volume,
+            None => &[],
+        }
+    }
+}
+
+/// Indicator outputs that can be turned into arrays: a single output becomes an
+/// `Array1`, several become the columns of an `Array2`.
+pub trait IntoArray {
+    type Array;
+
+    fn into_array(self) -> Self::Array;
+}
+
+impl<T> IntoArray for Vec<T> {
+    type Array = Array1<T>;
+
+    fn into_array(self) -> Self::Array {
+        Array1::from(self)
+    }
+}
+
+fn stack_columns<T: Copy>(columns: &[Vec<T>]) -> Array2<T> {
+    let rows = columns[0].len();
+    Array2::from_shape_fn((rows, columns.len()), |(i, j)| columns[j][i])
+}
+
+impl<T: Copy> IntoArray for (Vec<T>, Vec<T>) {
+    type Array = Array2<T>;
+
+    fn into_array(self) -> Self::Array {
+        stack_columns(&[self.0, self.1])
+    }
+}
+
+impl<T: Copy> IntoArray for (Vec<T>, Vec<T>, Vec<T>) {
+    type Array = Array2<T>;
+
+    fn into_array(self) -> Self::Array {
+        stack_columns(&[self.0, self.1, self.2])
+    }
+}
+
+impl<T: IntoArray> IndicatorOutput<T> {
+    /// Returns the computed values as an array, without padding.
+    pub fn into_array(self) -> T::Array {
+        self.into_values().into_array()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, ShapeBuilder};
+
+    use super::*;
+
+    #[test]
+    fn borrows_contiguous_columns() {
+        let data = array![1.0, 2.0, 3.0];
+        let column = ArrayColumn::new(data.view()).unwrap();
+        assert!(matches!(column.0, Cow::Borrowed(_)));
+
+        let data = array![[1.0, 2.0], [3.0, 4.0]];
+        assert_eq!(
+            ArrayColumn::new(data.column(0)).unwrap_err(),
+            ArrayError::NotContiguous
+        );
+
+        let column = ArrayColumn::copied(data.column(0));
+        assert_eq!(column.as_ref(), &[1.0, 3.0]);
+    }
+
+    #[test]
+    fn reads_ohlcv_columns() {
+        let rows = array![[1.5, 3.0, 1.0, 2.0], [2.5, 4.0, 2.0, 3.0]];
+        assert_eq!(
+            OhlcvArray::new(rows.view()).unwrap_err(),
+            ArrayError::NotContiguous
+        );
+
+        let ohlcv = OhlcvArray::copied(rows.view()).unwrap();
+        assert_eq!((&ohlcv).high(), &[3.0, 4.0]);
+        assert!((&ohlcv).volume().is_empty());
+
+        let mut columns = Array2::zeros((2, 4).f());
+        columns.assign(&rows);
+        let ohlcv = OhlcvArray::new(columns.view()).unwrap();
+        assert_eq!((&ohlcv).close(), &[2.0, 3.0]);
+
+        assert_eq!(
+            OhlcvArray::copied(rows.slice(ndarray::s![.., ..3])).unwrap_err(),
+            ArrayError::ColumnCount(3)
+        );
+    }
+
+    #[test]
+    fn stacks_outputs_as_columns() {
+        let output = (vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]).into_array();
+        assert_eq!(output, array![[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    input::{Close, High, InputFlags, Length, Low, Open, OpenInterest, Timestamp, Volume},
+    series::Bar,
+};
+
+/// A single bar from a feed, read field by field when transposing bars into [`BarColumns`].
+///
+/// Only the fields in the `InputFlags` the columns are built with are read. Feeds without
+/// timestamps or open interest can leave those methods out: the timestamp then defaults
+/// to `0` and the open interest to `NaN`.
+pub trait BarLike {
+    fn open(&self) -> f64;
+    fn high(&self) -> f64;
+    fn low(&self) -> f64;
+    fn close(&self) -> f64;
+    fn volume(&self) -> f64;
+
+    fn open_interest(&self) -> f64 {
+        f64::NAN
+    }
+
+    fn timestamp(&self) -> i64 {
+        0
+    }
+}
+
+impl<B: BarLike> BarLike for &B {
+    fn open(&self) -> f64 {
+        B::open(self)
+    }
+    fn high(&self) -> f64 {
+        B::high(self)
+    }
+    fn low(&self) -> f64 {
+        B::low(self)
+    }
+    fn close(&self) -> f64 {
+        B::close(self)
+    }
+    fn volume(&self) -> f64 {
+        B::volume(self)
+    }
+    fn open_interest(&self) -> f64 {
+        B::open_interest(self)
+    }
+    fn timestamp(&self) -> i64 {
+        B::timestamp(self)
+    }
+}
+
+impl BarLike for Bar {
+    fn open(&self) -> f64 {
+        self.open
+    }
+    fn high(&self) -> f64 {
+        self.high
+    }
+    fn low(&self) -> f64 {
+        self.low
+    }
+    fn close(&self) -> f64 {
+        self.close
+    }
+    fn volume(&self) -> f64 {
+        self.volume
+    }
+    fn open_interest(&self) -> f64 {
+        self.open_interest.unwrap_or(f64::NAN)
+    }
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+/// Bars transposed into the columns an indicator reads.
+///
+/// Only the columns in the `InputFlags` the columns are built with are filled, the
+/// rest stay empty. Indicators taking a single real input read the close column, and
+/// each generated indicator has an `INPUT_FLAGS` constant with the columns it reads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BarColumns {
+    flags: InputFlags,
+    len: usize,
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+    open_interest: Vec<f64>,
+    timestamp: Vec<i64>,
+}
+
+impl BarColumns {
+    pub fn new<I>(bars: I, flags: InputFlags) -> Self
+    where
+        I: IntoIterator,
+        I::Item: BarLike,
+    {
+        let mut columns = Self::with_flags(flags);
+
+        for bar in bars {
+            columns.push(bar);
+        }
+
+        columns
+    }
+
+    /// Creates empty columns to push bars onto.
+    pub fn with_flags(flags: InputFlags) -> Self {
+        Self {
+            flags,
+            ..Self::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a bar to the columns the flags ask for.
+    pub fn push<B: BarLike>(&mut self, bar: B) {
+        let flags = self.flags;
+
+        if flags.contains(InputFlags::OPEN) {
+            self.open.push(bar.open());
+        }
+        if flags.contains(InputFlags::HIGH) {
+            self.high.push(bar.high());
+        }
+        if flags.contains(InputFlags::LOW) {
+            self.low.push(bar.low());
+        }
+        if flags.contains(InputFlags::CLOSE) {
+            self.close.push(bar.close());
+        }
+        if flags.contains(InputFlags::VOLUME) {
+            self.volume.push(bar.volume());
+        }
+        if flags.contains(InputFlags::OPEN_INTEREST) {
+            self.open_interest.push(bar.open_interest());
+        }
+        if flags.contains(InputFlags::TIMESTAMP) {
+            self.timestamp.push(bar.timestamp());
+        }
+
+        self.len += 1;
+    }
+
+    /// Drops the oldest `count` bars.
+    pub(crate) fn remove_oldest(&mut self, count: usize) {
+        let count = count.min(self.len);
+
+        // Columns that aren't filled are empty, so there is nothing to drain from them
+        let drain = |column: &mut Vec<f64>| {
+            column.drain(..count.min(column.len()));
+        };
+        drain(&mut self.open);
+        drain(&mut self.high);
+        drain(&mut self.low);
+        drain(&mut self.close);
+        drain(&mut self.volume);
+        drain(&mut self.open_interest);
+        self.timestamp.drain(..count.min(self.timestamp.len()));
+
+        self.len -= count;
+    }
+}
+
+impl AsRef<[f64]> for BarColumns {
+    fn as_ref(&self) -> &[f64] {
+        &self.close
+    }
+}
+
+impl Length for &BarColumns {
+    fn length(&self) -> usize {
+        self.len
+    }
+}
+
+impl Open for &BarColumns {
+    fn open(&self) -> &[f64] {
+        &self.open
+    }
+}
+impl High for &BarColumns {
+    fn high(&self) -> &[f64] {
+        &self.high
+    }
+}
+impl Low for &BarColumns {
+    fn low(&self) -> &[f64] {
+        &self.low
+    }
+}
+impl Close for &BarColumns {
+    fn close(&self) -> &[f64] {
+        &self.close
+    }
+}
+impl Volume for &BarColumns {
+    fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+}
+impl OpenInterest for &BarColumns {
+    fn open_interest(&self) -> &[f64] {
+        &self.open_interest
+    }
+}
+impl Timestamp for &BarColumns {
+    fn timestamp(&self) -> &[i64] {
+        &self.timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_builds_requested_columns() {
+        let bars = vec![
+            Bar {
+                timestamp: 1,
+                open: 1.5,
+                high: 3.0,
+                low: 1.0,
+                close: 2.0,
+                volume: 10.0,
+                open_interest: None,
+            },
+            Bar {
+                timestamp: 2,
+                open: 2.5,
+                high: 4.0,
+                low: 2.0,
+                close: 3.0,
+                volume: 20.0,
+                open_interest: None,
+            },
+        ];
+
+        let columns = BarColumns::new(&bars, InputFlags::HIGH | InputFlags::LOW);
+        assert_eq!((&columns).high(), &[3.0, 4.0]);
+        assert_eq!((&columns).low(), &[1.0, 2.0]);
+        assert!((&columns).open().is_empty());
+        assert!((&columns).close().is_empty());
+        assert!((&columns).timestamp().is_empty());
+        assert_eq!((&columns).length(), 2);
+    }
+}
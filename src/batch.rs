@@ -0,0 +1,295 @@
+use std::{borrow::Cow, error::Error, fmt, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, Float64Array, Int32Array},
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+
+use crate::{
+    input::{Close, High, Length, Low, Open, Volume},
+    output::{IndicatorOutput, Outputs, INTEGER_SENTINEL},
+};
+
+/// What to do with null values in an input array.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum NullPolicy {
+    /// Reject arrays containing nulls.
+    #[default]
+    Fail,
+    /// Replace each null with the last value before it. Leading nulls have nothing
+    /// to fill from and become NaN.
+    ForwardFill,
+}
+
+/// Error returned when a record batch can't be used as indicator input.
+#[derive(Debug, PartialEq)]
+pub enum BatchError {
+    /// The column holds a null at `index` and the policy is [`NullPolicy::Fail`].
+    Null { column: String, index: usize },
+    /// The column isn't a `Float64` array.
+    NotFloat64 { column: String, data_type: DataType },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Null { column, index } => {
+                write!(f, "Column {} has a null at index {}", column, index)
+            }
+            BatchError::NotFloat64 { column, data_type } => {
+                write!(f, "Column {} is {} rather than Float64", column, data_type)
+            }
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+/// Borrows the array's values if it has no nulls, otherwise applies the policy.
+fn column_values<'a>(
+    column: &str,
+    array: &'a Float64Array,
+    policy: NullPolicy,
+) -> Result<Cow<'a, [f64]>, BatchError> {
+    let nulls = match array.nulls() {
+        Some(nulls) if nulls.null_count() > 0 => nulls,
+        _ => return Ok(Cow::Borrowed(array.values())),
+    };
+
+    match policy {
+        NullPolicy::Fail => Err(BatchError::Null {
+            column: column.to_string(),
+            index: (0..array.len()).find(|&i| nulls.is_null(i)).unwrap(),
+        }),
+        NullPolicy::ForwardFill => {
+            let mut last = f64::NAN;
+            let values = array
+                .iter()
+                .map(|value| {
+                    if let Some(value) = value {
+                        last = value;
+                    }
+                    last
+                })
+                .collect();
+
+            Ok(Cow::Owned(values))
+        }
+    }
+}
+
+/// A `Float64Array` passed as a real input.
+#[derive(Debug, Clone)]
+pub struct ArrowColumn<'a>(Cow<'a, [f64]>);
+
+impl<'a> ArrowColumn<'a> {
+    /// Borrows the array's buffer, or fills its nulls according to the policy.
+    pub fn new(array: &'a Float64Array, policy: NullPolicy) -> Result<Self, BatchError> {
+        column_values("input", array, policy).map(Self)
+    }
+}
+
+impl<'a> AsRef<[f64]> for ArrowColumn<'a> {
+    fn as_ref(&self) -> &[f64] {
+        &self.0
+    }
+}
+
+impl<'a> Length for ArrowColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> Length for &ArrowColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// The `open`, `high`, `low`, `close` and `volume` columns of a record batch, passed
+/// as price input.
+///
+/// Columns are borrowed without copying unless they hold nulls to fill. Columns the
+/// batch doesn't have stay empty, so only indicators reading them fail. Indicators
+/// taking a single real input read the close column.
+#[derive(Debug, Clone)]
+pub struct BatchColumns<'a> {
+    len: usize,
+    open: Cow<'a, [f64]>,
+    high: Cow<'a, [f64]>,
+    low: Cow<'a, [f64]>,
+    close: Cow<'a, [f64]>,
+    volume: Cow<'a, [f64]>,
+}
+
+impl<'a> BatchColumns<'a> {
+    pub fn new(batch: &'a RecordBatch, policy: NullPolicy) -> Result<Self, BatchError> {
+        let column = |name: &str| {
+            let array = match batch.column_by_name(name) {
+                Some(array) => array,
+                None => return Ok(Cow::Borrowed(&[][..])),
+            };
+
+            match array.as_any().downcast_ref::<Float64Array>() {
+                Some(array) => column_values(name, array, policy),
+                None => Err(BatchError::NotFloat64 {
+                    column: name.to_string(),
+                    data_type: array.data_type().clone(),
+                }),
+            }
+        };
+
+        Ok(Self {
+            len: batch.num_rows(),
+            open: column("open")?,
+            high: column("high")?,
+            low: column("low")?,
+            close: column("close")?,
+            volume: column("volume")?,
+        })
+    }
+}
+
+impl<'a> AsRef<[f64]> for BatchColumns<'a> {
+    fn as_ref(&self) -> &[f64] {
+        &self.close
+    }
+}
+
+impl<'a> Length for &BatchColumns<'a> {
+    fn length(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a> Open for &BatchColumns<'a> {
+    fn open(&self) -> &[f64] {
+        &self.open
+    }
+}
+impl<'a> High for &BatchColumns<'a> {
+    fn high(&self) -> &[f64] {
+        &self.high
+    }
+}
+impl<'a> Low for &BatchColumns<'a> {
+    fn low(&self) -> &[f64] {
+        &self.low
+    }
+}
+impl<'a> Close for &BatchColumns<'a> {
+    fn close(&self) -> &[f64] {
+        &self.close
+    }
+}
+impl<'a> Volume for &BatchColumns<'a> {
+    fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+}
+
+/// Indicator outputs that can be turned into arrow arrays, one per output.
+///
+/// Real outputs keep NaN for missing values, while integer outputs turn
+/// [`INTEGER_SENTINEL`] into nulls.
+pub trait IntoArrays {
+    fn into_arrays(self) -> Vec<ArrayRef>;
+}
+
+impl IntoArrays for Vec<f64> {
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        vec![Arc::new(Float64Array::from(self))]
+    }
+}
+
+impl IntoArrays for Vec<i32> {
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        let values: Int32Array = self
+            .into_iter()
+            .map(|value| Some(value).filter(|&value| value != INTEGER_SENTINEL))
+            .collect();
+
+        vec![Arc::new(values)]
+    }
+}
+
+impl<A: IntoArrays, B: IntoArrays> IntoArrays for (A, B) {
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        let mut arrays = self.0.into_arrays();
+        arrays.extend(self.1.into_arrays());
+        arrays
+    }
+}
+
+impl<A: IntoArrays, B: IntoArrays, C: IntoArrays> IntoArrays for (A, B, C) {
+    fn into_arrays(self) -> Vec<ArrayRef> {
+        let mut arrays = self.0.into_arrays();
+        arrays.extend(self.1.into_arrays());
+        arrays.extend(self.2.into_arrays());
+        arrays
+    }
+}
+
+impl<T: Outputs + IntoArrays> IndicatorOutput<T> {
+    /// Returns the values aligned with the input as a record batch with a column per
+    /// output, named after TA-Lib's output names. Those are in each indicator's
+    /// `OUTPUT_NAMES`, as in `output.into_record_batch(Bbands::OUTPUT_NAMES)`.
+    pub fn into_record_batch(self, output_names: &[&str]) -> Result<RecordBatch, ArrowError> {
+        let arrays = self.into_aligned().into_arrays();
+        let fields: Vec<_> = output_names
+            .iter()
+            .zip(&arrays)
+            .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+            .collect();
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrows_arrays_without_nulls() {
+        let array = Float64Array::from(vec![1.0, 2.0, 3.0]);
+        let column = ArrowColumn::new(&array, NullPolicy::Fail).unwrap();
+        assert_eq!(column.as_ref().as_ptr(), array.values().as_ptr());
+    }
+
+    #[test]
+    fn applies_null_policy() {
+        let array = Float64Array::from(vec![None, Some(1.0), None, Some(3.0)]);
+        assert_eq!(
+            ArrowColumn::new(&array, NullPolicy::Fail).unwrap_err(),
+            BatchError::Null {
+                column: "input".to_string(),
+                index: 0
+            }
+        );
+
+        let column = ArrowColumn::new(&array, NullPolicy::ForwardFill).unwrap();
+        assert!(column.as_ref()[0].is_nan());
+        assert_eq!(&column.as_ref()[1..], &[1.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn rejects_other_column_types() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "close",
+            Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+        )])
+        .unwrap();
+
+        assert_eq!(
+            BatchColumns::new(&batch, NullPolicy::Fail).unwrap_err(),
+            BatchError::NotFloat64 {
+                column: "close".to_string(),
+                data_type: DataType::Int32
+            }
+        );
+    }
+}
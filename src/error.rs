@@ -0,0 +1,264 @@
+use std::{error::Error, fmt};
+
+pub use rustta_bindgen::types::TaError;
+
+/// Error returned by an indicator's `prepare` and `calculate` functions.
+///
+/// `function` is the TA-Lib name of the indicator that failed, e.g. `"SMA"`.
+#[derive(Debug, PartialEq)]
+pub enum IndicatorError {
+    /// The TA-Lib function couldn't be looked up or its parameters couldn't be allocated.
+    Handle {
+        function: &'static str,
+        source: TaError,
+    },
+    /// TA-Lib rejected the input at `position`.
+    Input {
+        function: &'static str,
+        position: usize,
+        source: TaError,
+    },
+    /// TA-Lib rejected the optional parameter at `position`.
+    Param {
+        function: &'static str,
+        position: usize,
+        source: TaError,
+    },
+    /// TA-Lib rejected the output buffer at `position`.
+    Output {
+        function: &'static str,
+        position: usize,
+        source: TaError,
+    },
+    /// The lookback, and so the number of output values, couldn't be determined.
+    /// TA-Lib reports this when the parameters are out of range.
+    OutputSize { function: &'static str },
+    /// Input `column` has `len` values, but `expected_column`, the first column the
+    /// indicator reads, has `expected`.
+    LengthMismatch {
+        function: &'static str,
+        column: &'static str,
+        len: usize,
+        expected_column: &'static str,
+        expected: usize,
+    },
+    /// The input has `supplied` bars but the indicator needs at least `required`
+    /// to compute a single value.
+    NotEnoughBars {
+        function: &'static str,
+        required: usize,
+        supplied: usize,
+    },
+    /// A caller-provided output buffer is too small for the values TA-Lib will write.
+    BufferTooSmall {
+        function: &'static str,
+        output: &'static str,
+        len: usize,
+        required: usize,
+    },
+    /// The TA-Lib function itself failed.
+    Call {
+        function: &'static str,
+        source: TaError,
+    },
+    /// The indicator's values depend on every bar before them, like running totals, so
+    /// a stream keeping only its lookback can't reproduce them.
+    NotStreamable { function: &'static str },
+    /// The indicator warms up on the unstable period of `unstable`, which is `0`, so a
+    /// stream's values would start from a guess rather than match `calculate`.
+    UnstablePeriodNotSet {
+        function: &'static str,
+        unstable: &'static str,
+    },
+}
+
+impl IndicatorError {
+    /// TA-Lib name of the indicator that failed.
+    pub fn function(&self) -> &'static str {
+        match self {
+            IndicatorError::Handle { function, .. }
+            | IndicatorError::Input { function, .. }
+            | IndicatorError::Param { function, .. }
+            | IndicatorError::Output { function, .. }
+            | IndicatorError::OutputSize { function }
+            | IndicatorError::LengthMismatch { function, .. }
+            | IndicatorError::NotEnoughBars { function, .. }
+            | IndicatorError::BufferTooSmall { function, .. }
+            | IndicatorError::Call { function, .. }
+            | IndicatorError::NotStreamable { function }
+            | IndicatorError::UnstablePeriodNotSet { function, .. } => function,
+        }
+    }
+}
+
+impl fmt::Display for IndicatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndicatorError::Handle { function, source } => {
+                write!(f, "{}: failed to get function handle: {}", function, source)
+            }
+            IndicatorError::Input {
+                function,
+                position,
+                source,
+            } => write!(
+                f,
+                "{}: failed to set input {}: {}",
+                function, position, source
+            ),
+            IndicatorError::Param {
+                function,
+                position,
+                source,
+            } => write!(
+                f,
+                "{}: failed to set param {}: {}",
+                function, position, source
+            ),
+            IndicatorError::Output {
+                function,
+                position,
+                source,
+            } => write!(
+                f,
+                "{}: failed to set output {}: {}",
+                function, position, source
+            ),
+            IndicatorError::OutputSize { function } => {
+                write!(f, "{}: failed to get required output size", function)
+            }
+            IndicatorError::LengthMismatch {
+                function,
+                column,
+                len,
+                expected_column,
+                expected,
+            } => write!(
+                f,
+                "{}: input {} has {} values but {} has {}",
+                function, column, len, expected_column, expected
+            ),
+            IndicatorError::NotEnoughBars {
+                function,
+                required,
+                supplied,
+            } => write!(
+                f,
+                "{}: not enough bars, {} required but {} supplied",
+                function, required, supplied
+            ),
+            IndicatorError::BufferTooSmall {
+                function,
+                output,
+                len,
+                required,
+            } => write!(
+                f,
+                "{}: output buffer {} holds {} values but {} are required",
+                function, output, len, required
+            ),
+            IndicatorError::Call { function, source } => {
+                write!(f, "{}: call failed: {}", function, source)
+            }
+            IndicatorError::NotStreamable { function } => write!(
+                f,
+                "{}: values depend on every earlier bar, so it can't be streamed",
+                function
+            ),
+            IndicatorError::UnstablePeriodNotSet { function, unstable } => write!(
+                f,
+                "{}: the unstable period of {} must be set to stream it",
+                function, unstable
+            ),
+        }
+    }
+}
+
+impl Error for IndicatorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IndicatorError::Handle { source, .. }
+            | IndicatorError::Input { source, .. }
+            | IndicatorError::Param { source, .. }
+            | IndicatorError::Output { source, .. }
+            | IndicatorError::Call { source, .. } => Some(source),
+            IndicatorError::OutputSize { .. }
+            | IndicatorError::LengthMismatch { .. }
+            | IndicatorError::NotEnoughBars { .. }
+            | IndicatorError::BufferTooSmall { .. }
+            | IndicatorError::NotStreamable { .. }
+            | IndicatorError::UnstablePeriodNotSet { .. } => None,
+        }
+    }
+}
+
+/// Error returned when a bar doesn't fit an `OhlcvSeries`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SeriesError {
+    /// Bar `index` isn't later than the bar before it.
+    TimestampOutOfOrder {
+        index: usize,
+        timestamp: i64,
+        previous: i64,
+    },
+    /// Bar `index` has no open interest but the series stores one for every bar.
+    MissingOpenInterest { index: usize },
+    /// Bar `index` has an open interest but the series doesn't store any.
+    UnexpectedOpenInterest { index: usize },
+}
+
+impl fmt::Display for SeriesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeriesError::TimestampOutOfOrder {
+                index,
+                timestamp,
+                previous,
+            } => write!(
+                f,
+                "Bar {} has timestamp {} which isn't after the previous {}",
+                index, timestamp, previous
+            ),
+            SeriesError::MissingOpenInterest { index } => {
+                write!(f, "Bar {} is missing an open interest", index)
+            }
+            SeriesError::UnexpectedOpenInterest { index } => write!(
+                f,
+                "Bar {} has an open interest but the series has none",
+                index
+            ),
+        }
+    }
+}
+
+impl Error for SeriesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_be_sent_between_threads() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<IndicatorError>();
+    }
+
+    #[test]
+    fn exposes_function_and_source() {
+        let error = IndicatorError::Param {
+            function: "SMA",
+            position: 0,
+            source: TaError::BadParam("A parameter is out of range".into()),
+        };
+
+        assert_eq!(error.function(), "SMA");
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Bad param: A parameter is out of range"
+        );
+        assert_eq!(
+            error.to_string(),
+            "SMA: failed to set param 0: Bad param: A parameter is out of range"
+        );
+    }
+}
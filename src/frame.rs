@@ -0,0 +1,279 @@
+use std::borrow::Cow;
+
+use polars::prelude::*;
+
+use crate::{
+    input::{Close, High, Length, Low, Open, Volume},
+    output::{IndicatorOutput, Outputs, INTEGER_SENTINEL},
+};
+
+/// Borrows a series' values if it's a single chunk of `f64` without nulls, otherwise
+/// casts and copies it with nulls read as NaN.
+fn column_values(series: &Series) -> PolarsResult<Cow<'_, [f64]>> {
+    if let Ok(values) = series.f64().and_then(|values| values.cont_slice()) {
+        return Ok(Cow::Borrowed(values));
+    }
+
+    let values = series.cast(&DataType::Float64)?;
+    let values = values
+        .f64()?
+        .into_iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect();
+
+    Ok(Cow::Owned(values))
+}
+
+/// A named series passed as a real input.
+#[derive(Debug, Clone)]
+pub struct SeriesColumn<'a>(Cow<'a, [f64]>);
+
+impl<'a> SeriesColumn<'a> {
+    pub fn new(series: &'a Series) -> PolarsResult<Self> {
+        column_values(series).map(Self)
+    }
+}
+
+impl<'a> AsRef<[f64]> for SeriesColumn<'a> {
+    fn as_ref(&self) -> &[f64] {
+        &self.0
+    }
+}
+
+impl<'a> Length for SeriesColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> Length for &SeriesColumn<'a> {
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// The `open`, `high`, `low`, `close` and `volume` columns of a data frame, passed as
+/// price input.
+///
+/// Columns the frame doesn't have stay empty, so only indicators reading them fail.
+/// Indicators taking a single real input read the close column.
+#[derive(Debug, Clone)]
+pub struct FrameColumns<'a> {
+    len: usize,
+    open: Cow<'a, [f64]>,
+    high: Cow<'a, [f64]>,
+    low: Cow<'a, [f64]>,
+    close: Cow<'a, [f64]>,
+    volume: Cow<'a, [f64]>,
+}
+
+impl<'a> FrameColumns<'a> {
+    pub fn new(df: &'a DataFrame) -> PolarsResult<Self> {
+        let column = |name: &str| match df.column(name) {
+            Ok(series) => column_values(series),
+            Err(_) => Ok(Cow::Borrowed(&[][..])),
+        };
+
+        Ok(Self {
+            len: df.height(),
+            open: column("open")?,
+            high: column("high")?,
+            low: column("low")?,
+            close: column("close")?,
+            volume: column("volume")?,
+        })
+    }
+}
+
+impl<'a> AsRef<[f64]> for FrameColumns<'a> {
+    fn as_ref(&self) -> &[f64] {
+        &self.close
+    }
+}
+
+impl<'a> Length for &FrameColumns<'a> {
+    fn length(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a> Open for &FrameColumns<'a> {
+    fn open(&self) -> &[f64] {
+        &self.open
+    }
+}
+impl<'a> High for &FrameColumns<'a> {
+    fn high(&self) -> &[f64] {
+        &self.high
+    }
+}
+impl<'a> Low for &FrameColumns<'a> {
+    fn low(&self) -> &[f64] {
+        &self.low
+    }
+}
+impl<'a> Close for &FrameColumns<'a> {
+    fn close(&self) -> &[f64] {
+        &self.close
+    }
+}
+impl<'a> Volume for &FrameColumns<'a> {
+    fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+}
+
+/// Indicator outputs that can be turned into series, one per output.
+///
+/// Real outputs keep NaN for missing values, while integer outputs turn
+/// [`INTEGER_SENTINEL`] into nulls. There must be a name per output.
+pub trait IntoSeries {
+    fn into_series(self, names: &[String]) -> PolarsResult<Vec<Series>>;
+}
+
+/// The name of a single output, failing unless there is exactly one.
+fn single_name(names: &[String]) -> PolarsResult<&str> {
+    match names {
+        [name] => Ok(name),
+        _ => polars_bail!(
+            ShapeMismatch: "{} column names for a single output", names.len()
+        ),
+    }
+}
+
+/// Splits off the name of the first output from those of the others.
+fn split_first_name(names: &[String]) -> PolarsResult<(&[String], &[String])> {
+    if names.is_empty() {
+        polars_bail!(ShapeMismatch: "fewer column names than outputs");
+    }
+
+    Ok(names.split_at(1))
+}
+
+impl IntoSeries for Vec<f64> {
+    fn into_series(self, names: &[String]) -> PolarsResult<Vec<Series>> {
+        Ok(vec![Series::new(single_name(names)?, self)])
+    }
+}
+
+impl IntoSeries for Vec<i32> {
+    fn into_series(self, names: &[String]) -> PolarsResult<Vec<Series>> {
+        let name = single_name(names)?;
+        let values: Vec<_> = self
+            .into_iter()
+            .map(|value| Some(value).filter(|&value| value != INTEGER_SENTINEL))
+            .collect();
+
+        Ok(vec![Series::new(name, values)])
+    }
+}
+
+impl<A: IntoSeries, B: IntoSeries> IntoSeries for (A, B) {
+    fn into_series(self, names: &[String]) -> PolarsResult<Vec<Series>> {
+        let (first, rest) = split_first_name(names)?;
+        let mut series = self.0.into_series(first)?;
+        series.extend(self.1.into_series(rest)?);
+        Ok(series)
+    }
+}
+
+impl<A: IntoSeries, B: IntoSeries, C: IntoSeries> IntoSeries for (A, B, C) {
+    fn into_series(self, names: &[String]) -> PolarsResult<Vec<Series>> {
+        let (first, rest) = split_first_name(names)?;
+        let (second, rest) = split_first_name(rest)?;
+        let mut series = self.0.into_series(first)?;
+        series.extend(self.1.into_series(second)?);
+        series.extend(self.2.into_series(rest)?);
+        Ok(series)
+    }
+}
+
+/// Appends an indicator's outputs to a data frame as columns aligned with its rows.
+///
+/// Columns are named after the `label` and the output's suffix, so Bollinger Bands
+/// labelled `bbands_20` add `bbands_20_upper_band`, `bbands_20_middle_band` and
+/// `bbands_20_lower_band`, while an empty suffix names the column after the label alone.
+/// Both usually come from the indicator, as in
+/// `append_outputs(&mut df, &bbands.label(), Bbands::COLUMN_SUFFIXES, output)`. Fails
+/// with [`PolarsError::ShapeMismatch`] unless there is a suffix per output.
+pub fn append_outputs<T: Outputs + IntoSeries>(
+    df: &mut DataFrame,
+    label: &str,
+    column_suffixes: &[&str],
+    output: IndicatorOutput<T>,
+) -> PolarsResult<()> {
+    if output.input_len() != df.height() {
+        polars_bail!(
+            ShapeMismatch: "{} was calculated over {} rows but the frame has {}",
+            label, output.input_len(), df.height()
+        );
+    }
+
+    let names: Vec<String> = column_suffixes
+        .iter()
+        .map(|suffix| {
+            if suffix.is_empty() {
+                label.to_string()
+            } else {
+                format!("{}_{}", label, suffix)
+            }
+        })
+        .collect();
+
+    for series in output.into_aligned().into_series(&names)? {
+        df.with_column(series)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrows_float_columns() {
+        let df = df!(
+            "close" => &[1.0, 2.0, 3.0],
+            "volume" => &[10, 20, 30],
+        )
+        .unwrap();
+
+        let columns = FrameColumns::new(&df).unwrap();
+        assert!(matches!(columns.close, Cow::Borrowed(_)));
+        assert_eq!((&columns).volume(), &[10.0, 20.0, 30.0]);
+        assert!((&columns).high().is_empty());
+        assert_eq!((&columns).length(), 3);
+
+        let series = Series::new("close", &[Some(1.0), None]);
+        let column = SeriesColumn::new(&series).unwrap();
+        assert!(column.as_ref()[1].is_nan());
+    }
+
+    #[test]
+    fn appends_aligned_outputs() {
+        let mut df = df!("close" => &[1.0, 2.0, 3.0]).unwrap();
+        let output = IndicatorOutput::new(vec![5, INTEGER_SENTINEL], 1, 2, 1, 3);
+
+        append_outputs(&mut df, "minmaxindex_2", &[""], output).unwrap();
+
+        let column = df.column("minmaxindex_2").unwrap().i32().unwrap();
+        assert_eq!(
+            column.into_iter().collect::<Vec<_>>(),
+            vec![None, Some(5), None]
+        );
+    }
+
+    #[test]
+    fn needs_a_suffix_per_output() {
+        let mut df = df!("close" => &[1.0, 2.0]).unwrap();
+        let output = IndicatorOutput::new((vec![1.0, 2.0], vec![3.0, 4.0]), 0, 2, 0, 2);
+
+        let result = append_outputs(&mut df, "aroon_14", &["down"], output.clone());
+        assert!(matches!(result, Err(PolarsError::ShapeMismatch(_))));
+
+        let result = append_outputs(&mut df, "aroon_14", &["down", "up", "extra"], output);
+        assert!(matches!(result, Err(PolarsError::ShapeMismatch(_))));
+        assert_eq!(df.width(), 1);
+    }
+}
@@ -10,14 +10,18 @@ macro_rules! max {
     };
 }
 
-macro_rules! min {
-    ($x:expr) => ( $x );
-    ($x:expr, $($xs:expr),+) => {
-        {
-            use std::cmp::min;
-            min($x, min!( $($xs),+ ))
-        }
-    };
+/// Deterministic open, high, low, close and volume columns for the generated tests.
+#[cfg(test)]
+pub(crate) fn test_columns(len: usize) -> [Vec<f64>; 5] {
+    let close: Vec<f64> = (0..len)
+        .map(|i| 50.0 + 10.0 * (i as f64 / 7.0).sin() + i as f64 * 0.05)
+        .collect();
+    let open = close.iter().map(|x| x - 0.5).collect();
+    let high = close.iter().map(|x| x + 1.0).collect();
+    let low = close.iter().map(|x| x - 1.0).collect();
+    let volume = close.iter().map(|x| x * 100.0).collect();
+
+    [open, high, low, close, volume]
 }
 
 include!(concat!(env!("OUT_DIR"), "/indicators.rs"));
@@ -1,23 +1,41 @@
+use crate::error::IndicatorError;
+
+pub use rustta_bindgen::meta::params::input::InputFlags;
+pub use rustta_derive::RusttaInput;
+
+/// Number of bars in an input. Inputs with several columns report their longest one:
+/// indicators check that the columns they read have the same length before calculating,
+/// and return `IndicatorError::LengthMismatch` if they don't.
 pub trait Length {
     fn length(&self) -> usize;
 }
 
-pub trait Open {
-    fn open(&self) -> &[f64];
+pub trait Open<T = f64> {
+    fn open(&self) -> &[T];
+}
+
+pub trait Low<T = f64> {
+    fn low(&self) -> &[T];
+}
+pub trait High<T = f64> {
+    fn high(&self) -> &[T];
 }
 
-pub trait Low {
-    fn low(&self) -> &[f64];
+pub trait Close<T = f64> {
+    fn close(&self) -> &[T];
 }
-pub trait High {
-    fn high(&self) -> &[f64];
+pub trait Volume<T = f64> {
+    fn volume(&self) -> &[T];
 }
 
-pub trait Close {
-    fn close(&self) -> &[f64];
+pub trait OpenInterest<T = f64> {
+    fn open_interest(&self) -> &[T];
 }
-pub trait Volume {
-    fn volume(&self) -> &[f64];
+
+/// Bar timestamps. TA-Lib doesn't read them, but they are checked to line up with
+/// the price columns.
+pub trait Timestamp {
+    fn timestamp(&self) -> &[i64];
 }
 
 impl<T> Length for &[T] {
@@ -32,7 +50,7 @@ impl<T> Length for Vec<T> {
     }
 }
 
-impl Length for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
+impl<T> Length for (&[T], &[T], &[T], &[T], &[T]) {
     fn length(&self) -> usize {
         max!(
             self.0.len(),
@@ -44,45 +62,103 @@ impl Length for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
     }
 }
 
-impl Open for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
-    fn open(&self) -> &[f64] {
-        self.0
+impl<T> Length for (&[T], &[T], &[T], &[T], &[T], &[T]) {
+    fn length(&self) -> usize {
+        max!(
+            self.0.len(),
+            self.1.len(),
+            self.2.len(),
+            self.3.len(),
+            self.4.len(),
+            self.5.len()
+        )
     }
 }
 
-impl Low for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
-    fn low(&self) -> &[f64] {
-        self.1
+impl<T> Length for (&[T], &[T], &[T], &[T], &[T], &[T], &[i64]) {
+    fn length(&self) -> usize {
+        max!(
+            self.0.len(),
+            self.1.len(),
+            self.2.len(),
+            self.3.len(),
+            self.4.len(),
+            self.5.len(),
+            self.6.len()
+        )
     }
 }
 
-impl High for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
-    fn high(&self) -> &[f64] {
-        self.2
+/// Implements the price traits for a tuple holding its columns in the order
+/// (open, low, high, close, volume, ...).
+macro_rules! impl_price_tuple {
+    ($tuple:ty) => {
+        impl<T> Open<T> for $tuple {
+            fn open(&self) -> &[T] {
+                self.0
+            }
+        }
+
+        impl<T> Low<T> for $tuple {
+            fn low(&self) -> &[T] {
+                self.1
+            }
+        }
+
+        impl<T> High<T> for $tuple {
+            fn high(&self) -> &[T] {
+                self.2
+            }
+        }
+
+        impl<T> Close<T> for $tuple {
+            fn close(&self) -> &[T] {
+                self.3
+            }
+        }
+
+        impl<T> Volume<T> for $tuple {
+            fn volume(&self) -> &[T] {
+                self.4
+            }
+        }
+    };
+}
+
+impl_price_tuple!((&[T], &[T], &[T], &[T], &[T]));
+impl_price_tuple!((&[T], &[T], &[T], &[T], &[T], &[T]));
+impl_price_tuple!((&[T], &[T], &[T], &[T], &[T], &[T], &[i64]));
+
+impl<T> OpenInterest<T> for (&[T], &[T], &[T], &[T], &[T], &[T]) {
+    fn open_interest(&self) -> &[T] {
+        self.5
     }
 }
 
-impl Close for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
-    fn close(&self) -> &[f64] {
-        self.3
+impl<T> OpenInterest<T> for (&[T], &[T], &[T], &[T], &[T], &[T], &[i64]) {
+    fn open_interest(&self) -> &[T] {
+        self.5
     }
 }
 
-impl Volume for (&[f64], &[f64], &[f64], &[f64], &[f64]) {
-    fn volume(&self) -> &[f64] {
-        self.4
+impl<T> Timestamp for (&[T], &[T], &[T], &[T], &[T], &[T], &[i64]) {
+    fn timestamp(&self) -> &[i64] {
+        self.6
     }
 }
 
-pub struct Ohlcv<'a> {
-    pub open: &'a [f64],
-    pub high: &'a [f64],
-    pub low: &'a [f64],
-    pub close: &'a [f64],
-    pub volume: &'a [f64],
+/// Borrowed price columns, as `f64` or, for the `calculate_f32` functions, as `f32`.
+pub struct Ohlcv<'a, T = f64> {
+    pub open: &'a [T],
+    pub high: &'a [T],
+    pub low: &'a [T],
+    pub close: &'a [T],
+    pub volume: &'a [T],
+    pub open_interest: &'a [T],
+    pub timestamp: &'a [i64],
 }
 
-impl<'a> Default for Ohlcv<'a> {
+impl<'a, T> Default for Ohlcv<'a, T> {
     fn default() -> Self {
         Self {
             open: &[],
@@ -90,44 +166,92 @@ impl<'a> Default for Ohlcv<'a> {
             low: &[],
             close: &[],
             volume: &[],
+            open_interest: &[],
+            timestamp: &[],
         }
     }
 }
 
-impl<'a> Length for &Ohlcv<'a> {
+impl<'a, T> Length for &Ohlcv<'a, T> {
     fn length(&self) -> usize {
         max!(
             self.open.len(),
             self.high.len(),
             self.low.len(),
             self.close.len(),
-            self.volume.len()
+            self.volume.len(),
+            self.open_interest.len(),
+            self.timestamp.len()
         )
     }
 }
 
-impl<'a> Open for &Ohlcv<'a> {
-    fn open(&self) -> &[f64] {
+impl<'a, T> Open<T> for &Ohlcv<'a, T> {
+    fn open(&self) -> &[T] {
         self.open
     }
 }
-impl<'a> High for &Ohlcv<'a> {
-    fn high(&self) -> &[f64] {
+impl<'a, T> High<T> for &Ohlcv<'a, T> {
+    fn high(&self) -> &[T] {
         self.high
     }
 }
-impl<'a> Low for &Ohlcv<'a> {
-    fn low(&self) -> &[f64] {
+impl<'a, T> Low<T> for &Ohlcv<'a, T> {
+    fn low(&self) -> &[T] {
         self.low
     }
 }
-impl<'a> Close for &Ohlcv<'a> {
-    fn close(&self) -> &[f64] {
+impl<'a, T> Close<T> for &Ohlcv<'a, T> {
+    fn close(&self) -> &[T] {
         self.close
     }
 }
-impl<'a> Volume for &Ohlcv<'a> {
-    fn volume(&self) -> &[f64] {
+impl<'a, T> Volume<T> for &Ohlcv<'a, T> {
+    fn volume(&self) -> &[T] {
         self.volume
     }
 }
+impl<'a, T> OpenInterest<T> for &Ohlcv<'a, T> {
+    fn open_interest(&self) -> &[T] {
+        self.open_interest
+    }
+}
+impl<'a, T> Timestamp for &Ohlcv<'a, T> {
+    fn timestamp(&self) -> &[i64] {
+        self.timestamp
+    }
+}
+
+/// What an indicator returns when the input doesn't have more bars than its lookback,
+/// so TA-Lib can't compute a single value.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ShortInputPolicy {
+    /// Return `IndicatorError::NotEnoughBars`.
+    #[default]
+    Error,
+    /// Return an output without any values.
+    Empty,
+}
+
+/// Checks that every `(name, length)` column an indicator reads has the same length
+/// and returns it. TA-Lib would otherwise read past the end of the shorter columns.
+pub(crate) fn common_length(
+    function: &'static str,
+    columns: &[(&'static str, usize)],
+) -> Result<usize, IndicatorError> {
+    let (first, expected) = columns[0];
+
+    for &(column, len) in &columns[1..] {
+        if len != expected {
+            return Err(IndicatorError::LengthMismatch {
+                function,
+                column,
+                len,
+                expected_column: first,
+                expected,
+            });
+        }
+    }
+
+    Ok(expected)
+}
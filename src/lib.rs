@@ -4,15 +4,33 @@ extern crate derive_builder;
 #[cfg_attr(test, macro_use)]
 extern crate approx;
 
+// Lets the code `#[derive(RusttaInput)]` generates refer to `::rustta` inside this crate too
+extern crate self as rustta;
+
+#[cfg(feature = "ndarray")]
+pub mod array;
+pub mod bars;
+#[cfg(feature = "arrow")]
+pub mod batch;
+pub mod error;
+#[cfg(feature = "polars")]
+pub mod frame;
 pub mod indicators;
 pub mod input;
+pub mod output;
+pub mod prepared;
+pub mod series;
+pub mod stream;
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
+    use super::error::IndicatorError;
     use super::indicators::overlap_studies::*;
     use super::indicators::price_transform::*;
+    use super::indicators::MaType;
+    use super::input::ShortInputPolicy;
 
     mod real_input_indicator {
         use super::*;
@@ -24,7 +42,7 @@ mod tests {
 
             let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
             let output = sma.calculate(data)?;
-            assert_relative_eq!(output.as_slice(), expected.as_slice());
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
 
             Ok(())
         }
@@ -36,14 +54,482 @@ mod tests {
 
             let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
             let output = sma.calculate(data.as_slice())?;
-            assert_relative_eq!(output.as_slice(), expected.as_slice());
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
+
+            Ok(())
+        }
+
+        #[test]
+        fn reports_alignment() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+
+            let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let output = sma.calculate(data)?;
+            assert_eq!(output.begin_index(), 3);
+            assert_eq!(output.lookback(), 3);
+            assert_eq!(output.len(), 2);
+            assert_eq!(output.input_len(), 5);
+            assert_eq!(output.input_index(0), Some(3));
+
+            Ok(())
+        }
+
+        #[test]
+        fn aligns_with_input() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+
+            let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let output = sma.calculate_aligned(data)?;
+            assert_eq!(output.len(), 5);
+            assert!(output[..3].iter().all(|x| x.is_nan()));
+            assert_relative_eq!(&output[3..], &[2.5, 3.5][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn prepared_can_be_reused() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+            let mut prepared = sma.prepare()?;
+
+            let first = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let second = vec![2.0, 4.0, 6.0, 8.0, 10.0, 12.0];
+
+            let output = prepared.calculate(first.as_slice())?;
+            assert_eq!(output, sma.calculate(first.as_slice())?);
+
+            let output = prepared.calculate(second.as_slice())?;
+            assert_eq!(output, sma.calculate(second.as_slice())?);
+            assert_relative_eq!(output.values().as_slice(), &[5.0, 7.0, 9.0][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "direct")]
+        fn direct_matches_abstract() -> Result<(), Box<dyn Error>> {
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+
+            let data = vec![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0];
+            let direct = bbands.calculate(data.as_slice())?;
+            let abstract_ = bbands.prepare()?.calculate(data.as_slice())?;
+            assert_eq!(direct, abstract_);
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "direct")]
+        fn direct_matches_abstract_with_ma_types() -> Result<(), Box<dyn Error>> {
+            use crate::indicators::momentum_indicators::MacdExtBuilder;
+
+            let macdext = MacdExtBuilder::default()
+                .fast_period(3)
+                .fast_ma_type(MaType::Wma)
+                .slow_period(5)
+                .slow_ma_type(MaType::Trima)
+                .signal_period(2)
+                .signal_ma_type(MaType::Wma)
+                .build()?;
+
+            let data: Vec<f64> = (0..40)
+                .map(|i| f64::from(i % 7) + f64::from(i) / 3.0)
+                .collect();
+            let direct = macdext.calculate(data.as_slice())?;
+            let abstract_ = macdext.prepare()?.calculate(data.as_slice())?;
+            assert_eq!(direct, abstract_);
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "ndarray")]
+        fn accepts_arrays() -> Result<(), Box<dyn Error>> {
+            use crate::array::ArrayColumn;
+            use ndarray::array;
+
+            let data = array![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0];
+            let column = ArrayColumn::new(data.view())?;
+
+            let sma = SmaBuilder::default().time_period(4).build()?;
+            let output = sma.calculate(&column)?.into_array();
+            assert_eq!(output.len(), 4);
+
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+            let output = bbands.calculate(&column)?;
+            let BbandsOutput {
+                upper_band,
+                middle_band,
+                lower_band,
+            } = output.values().clone();
+            let output = output.into_array();
+            assert_eq!(output.dim(), (5, 3));
+            assert_eq!(output.column(0).to_vec(), upper_band);
+            assert_eq!(output.column(1).to_vec(), middle_band);
+            assert_eq!(output.column(2).to_vec(), lower_band);
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "polars")]
+        fn appends_frame_columns() -> Result<(), Box<dyn Error>> {
+            use crate::frame::{append_outputs, FrameColumns};
+            use polars::prelude::*;
+
+            let mut df = df!("close" => &[1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0])?;
+
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+            let output = bbands.calculate(&FrameColumns::new(&df)?)?;
+            let upper = output.values().upper_band.clone();
+            append_outputs(&mut df, &bbands.label(), Bbands::COLUMN_SUFFIXES, output)?;
+
+            assert_eq!(
+                df.get_column_names(),
+                &[
+                    "close",
+                    "bbands_3_upper_band",
+                    "bbands_3_middle_band",
+                    "bbands_3_lower_band"
+                ]
+            );
+            let column: Vec<_> = df
+                .column("bbands_3_upper_band")?
+                .f64()?
+                .into_no_null_iter()
+                .collect();
+            assert!(column[1].is_nan());
+            assert_eq!(&column[2..], &upper[..]);
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "arrow")]
+        fn returns_record_batch() -> Result<(), Box<dyn Error>> {
+            use crate::batch::{BatchColumns, NullPolicy};
+            use arrow::array::{ArrayRef, Float64Array};
+            use arrow::record_batch::RecordBatch;
+            use std::sync::Arc;
+
+            let close = Float64Array::from(vec![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0]);
+            let batch = RecordBatch::try_from_iter(vec![("close", Arc::new(close) as ArrayRef)])?;
+
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+            let output = bbands.calculate(&BatchColumns::new(&batch, NullPolicy::Fail)?)?;
+            let upper = output.values().upper_band.clone();
+            let output = output.into_record_batch(Bbands::OUTPUT_NAMES)?;
+
+            assert_eq!(output.num_rows(), 7);
+            let schema = output.schema();
+            let names: Vec<_> = schema.fields().iter().map(|field| field.name()).collect();
+            assert_eq!(
+                names,
+                &["outRealUpperBand", "outRealMiddleBand", "outRealLowerBand"]
+            );
+            let column = output
+                .column(0)
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            assert!(column.value(1).is_nan());
+            assert_eq!(&column.values()[2..], &upper[..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn names_multiple_outputs() -> Result<(), Box<dyn Error>> {
+            let data = vec![1.0, 3.0, 2.0, 5.0, 4.0];
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+            let output = bbands.calculate(data.as_slice())?.into_values();
+
+            let bars: Vec<_> = output.iter().collect();
+            assert_eq!(bars.len(), 3);
+            assert_eq!(bars[1].middle_band, output.middle_band[1]);
+            assert!(bars.iter().all(|bar| bar.upper_band >= bar.lower_band));
+
+            let (upper, middle, lower) = output.clone().into();
+            assert_eq!(BbandsOutput::from((upper, middle, lower)), output);
+
+            Ok(())
+        }
+
+        #[test]
+        fn types_list_params() -> Result<(), Box<dyn Error>> {
+            let data = vec![1.0, 3.0, 2.0, 5.0, 4.0];
+            let bbands = BbandsBuilder::default().time_period(3).build()?;
+            assert_eq!(bbands.ma_type, MaType::Sma);
+
+            let bbands = BbandsBuilder::default()
+                .time_period(3)
+                .ma_type(MaType::Wma)
+                .build()?;
+            assert_eq!(bbands.calculate(data.as_slice())?.len(), 3);
+            assert_eq!(MaType::Ema.value(), 1);
+            assert_eq!(MaType::T3.to_string(), "T3");
+
+            Ok(())
+        }
+
+        #[test]
+        fn writes_into_buffer() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+
+            let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let mut buffer = vec![0.0; 4];
+            let written = sma.calculate_into(data.as_slice(), &mut buffer)?;
+            assert_eq!(written, 2);
+            assert_relative_eq!(&buffer[..written], &[2.5, 3.5][..]);
+
+            // Buffer too small for the two values SMA(4) produces
+            let mut buffer = vec![0.0; 1];
+            assert_eq!(
+                sma.calculate_into(data.as_slice(), &mut buffer),
+                Err(IndicatorError::BufferTooSmall {
+                    function: "SMA",
+                    output: "out_real",
+                    len: 1,
+                    required: 2,
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn rejects_short_input() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+
+            let data: Vec<f64> = vec![];
+            assert_eq!(
+                sma.calculate(data.as_slice()),
+                Err(IndicatorError::NotEnoughBars {
+                    function: "SMA",
+                    required: 4,
+                    supplied: 0,
+                })
+            );
+
+            let data = vec![1.0, 2.0, 3.0];
+            assert_eq!(
+                sma.calculate(data.as_slice()),
+                Err(IndicatorError::NotEnoughBars {
+                    function: "SMA",
+                    required: 4,
+                    supplied: 3,
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn short_input_can_be_empty() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default()
+                .time_period(4)
+                .short_input(ShortInputPolicy::Empty)
+                .build()?;
+
+            let data = vec![1.0, 2.0, 3.0];
+            let output = sma.calculate(data.as_slice())?;
+            assert!(output.is_empty());
+            assert_eq!(output.input_len(), 3);
+
+            let aligned = sma.calculate_aligned(data.as_slice())?;
+            assert_eq!(aligned.len(), 3);
+            assert!(aligned.iter().all(|x| x.is_nan()));
+
+            let mut buffer = vec![0.0; 3];
+            assert_eq!(sma.calculate_into(data.as_slice(), &mut buffer)?, 0);
+            assert!(sma.calculate(Vec::new())?.is_empty());
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_f32() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+
+            let data: Vec<f32> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let output = sma.calculate_f32(data)?;
+            assert_eq!(output.begin_index(), 3);
+            assert_relative_eq!(output.values().as_slice(), &[2.5, 3.5][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn reports_lookback_without_calculating() -> Result<(), Box<dyn Error>> {
+            let sma = SmaBuilder::default().time_period(4).build()?;
+            assert_eq!(sma.lookback()?, 3);
+
+            let bbands = BbandsBuilder::default().time_period(20).build()?;
+            assert_eq!(bbands.lookback()?, 19);
+
+            let data: Vec<f64> = (0..30).map(f64::from).collect();
+            assert_eq!(bbands.calculate(data)?.lookback(), bbands.lookback()?);
+
+            // Only reachable by setting the field directly, as the builder rejects it
+            let sma = Sma {
+                time_period: 1,
+                ..sma
+            };
+            assert!(sma.lookback().is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn builder_rejects_out_of_range_params() {
+            let error = SmaBuilder::default().time_period(-5).build().unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "time_period must be between 2 and 100000, got -5"
+            );
+
+            let error = MamaBuilder::default().fast_limit(1.5).build().unwrap_err();
+            assert!(error.to_string().starts_with("fast_limit must be between"));
+
+            let bbands = BbandsBuilder::default().nb_dev_up(-1.0).build();
+            assert!(bbands.is_ok());
+        }
+
+        #[test]
+        fn builder_defaults_to_ta_lib_defaults() -> Result<(), Box<dyn Error>> {
+            use rustta_bindgen::meta::{func_handle::FuncHandle, func_info::FuncInfo};
+            use std::convert::TryFrom;
+
+            let sar = SarBuilder::default().build()?;
+            let info = FuncInfo::try_from(FuncHandle::try_from("SAR")?)?;
+            let defaults: Vec<f64> = info.params().iter().map(|param| param.default()).collect();
+            assert_eq!(defaults, vec![sar.acceleration, sar.maximum]);
+            assert_eq!(sar.acceleration, 0.02);
+
+            Ok(())
+        }
+
+        #[test]
+        fn update_matches_calculate() -> Result<(), Box<dyn Error>> {
+            let data = vec![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0, 7.0];
+
+            let sma = SmaBuilder::default().time_period(3).build()?;
+            let previous = sma.calculate(&data[..5])?;
+            let updated = sma.update(previous, data.as_slice())?;
+            assert_eq!(updated, sma.calculate(data.as_slice())?);
+
+            // Too few bars to have warmed up yet, so everything is calculated
+            let sma = SmaBuilder::default()
+                .time_period(3)
+                .short_input(ShortInputPolicy::Empty)
+                .build()?;
+            let previous = sma.calculate(&data[..2])?;
+            assert!(previous.is_empty());
+            assert_eq!(
+                sma.update(previous, data.as_slice())?,
+                sma.calculate(data.as_slice())?
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn update_recalculates_unstable_indicators() -> Result<(), Box<dyn Error>> {
+            use crate::indicators::momentum_indicators::{MacdBuilder, RsiBuilder};
+
+            // With the default unstable period of 0 these would start from a guess at the
+            // first new bar, so they are calculated in full
+            let data: Vec<f64> = (0..100).map(|i| 50.0 + (i as f64 / 7.0).sin()).collect();
+            let (first, all) = (&data[..80], data.as_slice());
+
+            let ema = EmaBuilder::default().time_period(5).build()?;
+            assert!(ema.depends_on_history());
+            let previous = ema.calculate(first)?;
+            assert_eq!(ema.update(previous, all)?, ema.calculate(all)?);
+
+            let rsi = RsiBuilder::default().time_period(5).build()?;
+            let mut prepared = rsi.prepare()?;
+            let previous = prepared.calculate(first)?;
+            assert_eq!(prepared.update(previous, all)?, rsi.calculate(all)?);
+
+            let macd = MacdBuilder::default().build()?;
+            let previous = macd.calculate(first)?;
+            assert_eq!(macd.update(previous, all)?, macd.calculate(all)?);
+
+            let bbands = BbandsBuilder::default().ma_type(MaType::Ema).build()?;
+            assert!(bbands.depends_on_history());
+            let previous = bbands.calculate(first)?;
+            assert_eq!(bbands.update(previous, all)?, bbands.calculate(all)?);
+
+            assert!(!SmaBuilder::default().build()?.depends_on_history());
+
+            Ok(())
+        }
+
+        #[test]
+        fn stream_matches_calculate() -> Result<(), Box<dyn Error>> {
+            let data = vec![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0, 7.0];
+
+            let sma = SmaBuilder::default().time_period(3).build()?;
+            let mut stream = sma.stream()?;
+            let streamed = data
+                .iter()
+                .map(|&x| stream.push(x))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(stream.window(), 3);
+            assert_eq!(stream.len(), 3);
+            let aligned = sma.calculate_aligned(data.as_slice())?;
+            assert_eq!(&streamed[..2], &[None, None]);
+            for (value, expected) in streamed[2..].iter().zip(&aligned[2..]) {
+                assert_relative_eq!(value.unwrap(), *expected);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn stream_needs_unstable_period() -> Result<(), Box<dyn Error>> {
+            use crate::indicators::momentum_indicators::MacdBuilder;
+
+            // With the default unstable period of 0 the window would start from a guess
+            // that calculating over the whole input has long forgotten
+            assert_eq!(Ema::unstable_period(), 0);
+            let ema = EmaBuilder::default().time_period(3).build()?;
+            assert_eq!(
+                ema.stream().err(),
+                Some(IndicatorError::UnstablePeriodNotSet {
+                    function: "EMA",
+                    unstable: "EMA",
+                })
+            );
+
+            let macd = MacdBuilder::default().build()?;
+            assert_eq!(
+                macd.stream().err(),
+                Some(IndicatorError::UnstablePeriodNotSet {
+                    function: "MACD",
+                    unstable: "EMA",
+                })
+            );
+
+            // Moving average types bring the unstable period of their average
+            let bbands = BbandsBuilder::default().ma_type(MaType::Ema).build()?;
+            assert!(matches!(
+                bbands.stream(),
+                Err(IndicatorError::UnstablePeriodNotSet { .. })
+            ));
+            let bbands = BbandsBuilder::default().ma_type(MaType::Wma).build()?;
+            assert_eq!(bbands.stream()?.window(), bbands.lookback()? + 1);
 
             Ok(())
         }
     }
 
     mod price_input_indicator {
-        use crate::input::Ohlcv;
+        use crate::bars::{BarColumns, BarLike};
+        use crate::input::{Length, Ohlcv, OpenInterest, RusttaInput, Timestamp};
+        use crate::series::{Bar, OhlcvSeries};
 
         use super::*;
 
@@ -59,7 +545,321 @@ mod tests {
                 ..Ohlcv::default()
             };
             let output = typprice.calculate(&data)?;
-            assert_relative_eq!(output.as_slice(), expected.as_slice());
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "direct")]
+        fn direct_matches_abstract() -> Result<(), Box<dyn Error>> {
+            use crate::indicators::momentum_indicators::StochBuilder;
+            use crate::indicators::pattern_recognition::Cdl3InsideBuilder;
+
+            let open: Vec<f64> = (0..40).map(|i| f64::from((i * 5) % 11)).collect();
+            let close: Vec<f64> = (0..40).map(|i| f64::from((i * 7) % 13)).collect();
+            let high: Vec<f64> = open
+                .iter()
+                .zip(&close)
+                .map(|(o, c)| o.max(*c) + 1.0)
+                .collect();
+            let low: Vec<f64> = open
+                .iter()
+                .zip(&close)
+                .map(|(o, c)| o.min(*c) - 1.0)
+                .collect();
+            let data = Ohlcv {
+                open: &open,
+                high: &high,
+                low: &low,
+                close: &close,
+                ..Ohlcv::default()
+            };
+
+            let stoch = StochBuilder::default()
+                .fast_k_period(4)
+                .slow_k_ma_type(MaType::Wma)
+                .build()?;
+            let direct = stoch.calculate(&data)?;
+            let abstract_ = stoch.prepare()?.calculate(&data)?;
+            assert_eq!(direct, abstract_);
+
+            let cdl3inside = Cdl3InsideBuilder::default().build()?;
+            let direct = cdl3inside.calculate(&data)?;
+            let abstract_ = cdl3inside.prepare()?.calculate(&data)?;
+            assert_eq!(direct, abstract_);
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_series() -> Result<(), Box<dyn Error>> {
+            let typprice = TypPriceBuilder::default().build()?;
+
+            let mut series = OhlcvSeries::new();
+            for (i, close) in [2.0, 3.0, 4.0, 5.0].iter().enumerate() {
+                series.push(Bar {
+                    timestamp: i as i64,
+                    open: close - 0.5,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close: *close,
+                    volume: 100.0,
+                    open_interest: None,
+                })?;
+            }
+
+            let output = typprice.calculate(&series)?;
+            assert_relative_eq!(output.values().as_slice(), &[2.0, 3.0, 4.0, 5.0][..]);
+
+            let output = typprice.calculate(&series.slice(1..3))?;
+            assert_relative_eq!(output.values().as_slice(), &[3.0, 4.0][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_derived_input() -> Result<(), Box<dyn Error>> {
+            #[derive(RusttaInput)]
+            struct Candles {
+                #[rustta(high)]
+                h: Vec<f64>,
+                #[rustta(low)]
+                l: Vec<f64>,
+                #[rustta(close)]
+                c: Vec<f64>,
+                #[rustta(timestamp)]
+                ts: Vec<i64>,
+                #[allow(dead_code)]
+                symbol: String,
+            }
+
+            let typprice = TypPriceBuilder::default().build()?;
+            let candles = Candles {
+                h: vec![3.0, 4.0, 5.0],
+                l: vec![1.0, 2.0, 3.0],
+                c: vec![2.0, 3.0, 4.0],
+                ts: vec![1, 2, 3],
+                symbol: "ES".into(),
+            };
+            assert_eq!((&candles).timestamp(), &[1, 2, 3]);
+
+            let output = typprice.calculate(&candles)?;
+            assert_relative_eq!(output.values().as_slice(), &[2.0, 3.0, 4.0][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_borrowed_derived_input() -> Result<(), Box<dyn Error>> {
+            #[derive(RusttaInput)]
+            struct Columns<'a> {
+                #[rustta(high)]
+                high: &'a [f64],
+                #[rustta(low)]
+                low: &'a [f64],
+            }
+
+            let medprice = MedPriceBuilder::default().build()?;
+            let columns = Columns {
+                high: &[3.0, 5.0],
+                low: &[1.0, 2.0],
+            };
+
+            let output = medprice.calculate(&columns)?;
+            assert_relative_eq!(output.values().as_slice(), &[2.0, 3.5][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_bar_columns() -> Result<(), Box<dyn Error>> {
+            struct Tick {
+                h: f64,
+                l: f64,
+                c: f64,
+            }
+
+            impl BarLike for Tick {
+                fn open(&self) -> f64 {
+                    unreachable!("TYPPRICE doesn't read open")
+                }
+                fn high(&self) -> f64 {
+                    self.h
+                }
+                fn low(&self) -> f64 {
+                    self.l
+                }
+                fn close(&self) -> f64 {
+                    self.c
+                }
+                fn volume(&self) -> f64 {
+                    unreachable!("TYPPRICE doesn't read volume")
+                }
+            }
+
+            let ticks: Vec<_> = [2.0, 3.0, 4.0, 5.0]
+                .iter()
+                .map(|c| Tick {
+                    h: c + 1.0,
+                    l: c - 1.0,
+                    c: *c,
+                })
+                .collect();
+
+            // Open and volume aren't read, so they're never asked for
+            let typprice = TypPriceBuilder::default().build()?;
+            let columns = BarColumns::new(&ticks, TypPrice::INPUT_FLAGS);
+            let output = typprice.calculate(&columns)?;
+            assert_relative_eq!(output.values().as_slice(), &[2.0, 3.0, 4.0, 5.0][..]);
+
+            let sma = SmaBuilder::default().time_period(2).build()?;
+            let columns = BarColumns::new(&ticks, Sma::INPUT_FLAGS);
+            let output = sma.calculate(&columns)?;
+            assert_relative_eq!(output.values().as_slice(), &[2.5, 3.5, 4.5][..]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn streams_bars() -> Result<(), Box<dyn Error>> {
+            let bars: Vec<_> = [2.0, 3.0, 4.0]
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Bar {
+                    timestamp: i as i64,
+                    open: c - 0.5,
+                    high: c + 1.0,
+                    low: c - 1.0,
+                    close: *c,
+                    volume: 100.0,
+                    open_interest: None,
+                })
+                .collect();
+
+            let typprice = TypPriceBuilder::default().build()?;
+            let mut stream = typprice.stream()?;
+            for bar in &bars {
+                assert_relative_eq!(stream.push(bar)?.unwrap(), bar.close);
+            }
+            assert_eq!(stream.len(), 1);
+
+            Ok(())
+        }
+
+        #[test]
+        fn refuses_to_stream_running_totals() -> Result<(), Box<dyn Error>> {
+            use crate::indicators::volume_indicators::AdBuilder;
+
+            // Every value adds to the one before, so no window reproduces AD's values
+            let ad = AdBuilder::default().build()?;
+            assert_eq!(
+                ad.stream().err(),
+                Some(IndicatorError::NotStreamable { function: "AD" })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_tuples_with_open_interest() -> Result<(), Box<dyn Error>> {
+            let typprice = TypPriceBuilder::default().build()?;
+            let expected = vec![2.0, 3.0, 4.0];
+
+            let open = [1.5, 2.5, 3.5];
+            let low = [1.0, 2.0, 3.0];
+            let high = [3.0, 4.0, 5.0];
+            let close = [2.0, 3.0, 4.0];
+            let volume = [10.0, 20.0, 30.0];
+            let open_interest = [100.0, 110.0, 120.0];
+            let timestamp = [1_600_000_000, 1_600_000_060, 1_600_000_120];
+
+            let data = (
+                &open[..],
+                &low[..],
+                &high[..],
+                &close[..],
+                &volume[..],
+                &open_interest[..],
+            );
+            assert_eq!(data.open_interest(), &open_interest);
+            let output = typprice.calculate(data)?;
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
+
+            let data = (
+                &open[..],
+                &low[..],
+                &high[..],
+                &close[..],
+                &volume[..],
+                &open_interest[..],
+                &timestamp[..],
+            );
+            assert_eq!(data.timestamp(), &timestamp);
+            let output = typprice.calculate(data)?;
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
+
+            Ok(())
+        }
+
+        #[test]
+        fn rejects_mismatched_columns() -> Result<(), Box<dyn Error>> {
+            let typprice = TypPriceBuilder::default().build()?;
+
+            // Open isn't read by TYPPRICE so its length doesn't matter
+            let data = Ohlcv {
+                open: &[1.0],
+                high: &[3.0, 4.0, 5.0],
+                low: &[1.0, 2.0],
+                close: &[2.0, 3.0, 4.0],
+                ..Ohlcv::default()
+            };
+            assert_eq!(
+                typprice.calculate(&data),
+                Err(IndicatorError::LengthMismatch {
+                    function: "TYPPRICE",
+                    column: "low",
+                    len: 2,
+                    expected_column: "high",
+                    expected: 3,
+                })
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn measures_longest_column() -> Result<(), Box<dyn Error>> {
+            let typprice = TypPriceBuilder::default().build()?;
+
+            let data = Ohlcv {
+                high: &[3.0, 4.0, 5.0],
+                low: &[1.0, 2.0],
+                close: &[2.0, 3.0, 4.0],
+                ..Ohlcv::default()
+            };
+            assert_eq!((&data).length(), 3);
+            assert!(matches!(
+                typprice.calculate(&data),
+                Err(IndicatorError::LengthMismatch { column: "low", .. })
+            ));
+
+            Ok(())
+        }
+
+        #[test]
+        fn accepts_f32_data() -> Result<(), Box<dyn Error>> {
+            let typprice = TypPriceBuilder::default().build()?;
+            let expected = vec![2.0, 3.0, 4.0, 5.0];
+
+            let data: Ohlcv<f32> = Ohlcv {
+                high: &[3.0, 4.0, 5.0, 6.0],
+                close: &[2.0, 3.0, 4.0, 5.0],
+                low: &[1.0, 2.0, 3.0, 4.0],
+                ..Ohlcv::default()
+            };
+            let output = typprice.calculate_f32(&data)?;
+            assert_relative_eq!(output.values().as_slice(), expected.as_slice());
 
             Ok(())
         }
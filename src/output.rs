@@ -0,0 +1,254 @@
+use std::ops::Range;
+
+use crate::error::IndicatorError;
+
+/// Integer outputs can't hold `NaN`, so bars without a computed value get this instead.
+pub const INTEGER_SENTINEL: i32 = i32::MIN;
+
+/// Values an indicator can produce, either a single output or a tuple of them.
+pub trait Outputs: Sized {
+    /// Pads the values so there is one entry per input bar, filling the first
+    /// `begin_index` bars with `f64::NAN` or [`INTEGER_SENTINEL`].
+    fn pad(self, begin_index: usize, input_len: usize) -> Self;
+
+    /// Appends values computed for later input bars.
+    fn append(&mut self, tail: Self);
+}
+
+fn pad_with<T: Copy>(values: Vec<T>, fill: T, begin_index: usize, input_len: usize) -> Vec<T> {
+    let mut padded = Vec::with_capacity(input_len);
+    padded.resize(begin_index, fill);
+    padded.extend(values);
+    padded.resize(input_len, fill);
+    padded
+}
+
+impl Outputs for Vec<f64> {
+    fn pad(self, begin_index: usize, input_len: usize) -> Self {
+        pad_with(self, f64::NAN, begin_index, input_len)
+    }
+
+    fn append(&mut self, tail: Self) {
+        self.extend(tail)
+    }
+}
+
+impl Outputs for Vec<i32> {
+    fn pad(self, begin_index: usize, input_len: usize) -> Self {
+        pad_with(self, INTEGER_SENTINEL, begin_index, input_len)
+    }
+
+    fn append(&mut self, tail: Self) {
+        self.extend(tail)
+    }
+}
+
+impl<A: Outputs, B: Outputs> Outputs for (A, B) {
+    fn pad(self, begin_index: usize, input_len: usize) -> Self {
+        (
+            self.0.pad(begin_index, input_len),
+            self.1.pad(begin_index, input_len),
+        )
+    }
+
+    fn append(&mut self, tail: Self) {
+        self.0.append(tail.0);
+        self.1.append(tail.1);
+    }
+}
+
+impl<A: Outputs, B: Outputs, C: Outputs> Outputs for (A, B, C) {
+    fn pad(self, begin_index: usize, input_len: usize) -> Self {
+        (
+            self.0.pad(begin_index, input_len),
+            self.1.pad(begin_index, input_len),
+            self.2.pad(begin_index, input_len),
+        )
+    }
+
+    fn append(&mut self, tail: Self) {
+        self.0.append(tail.0);
+        self.1.append(tail.1);
+        self.2.append(tail.2);
+    }
+}
+
+/// Makes sure a caller-provided output buffer can hold the `required` values TA-Lib will write.
+pub(crate) fn check_output_buffer(
+    function: &'static str,
+    output: &'static str,
+    len: usize,
+    required: usize,
+) -> Result<(), IndicatorError> {
+    if len < required {
+        return Err(IndicatorError::BufferTooSmall {
+            function,
+            output,
+            len,
+            required,
+        });
+    }
+
+    Ok(())
+}
+
+/// Values computed by an indicator along with how they line up with the input bars.
+///
+/// TA-Lib only produces values once it has seen `lookback` bars, so the first
+/// computed value belongs to input bar `begin_index` rather than bar `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorOutput<T> {
+    values: T,
+    begin_index: usize,
+    len: usize,
+    lookback: usize,
+    input_len: usize,
+}
+
+impl<T> IndicatorOutput<T> {
+    pub(crate) fn new(
+        values: T,
+        begin_index: usize,
+        len: usize,
+        lookback: usize,
+        input_len: usize,
+    ) -> Self {
+        Self {
+            values,
+            begin_index,
+            len,
+            lookback,
+            input_len,
+        }
+    }
+
+    pub fn values(&self) -> &T {
+        &self.values
+    }
+
+    pub fn into_values(self) -> T {
+        self.values
+    }
+
+    /// Index of the input bar the first computed value belongs to.
+    pub fn begin_index(&self) -> usize {
+        self.begin_index
+    }
+
+    /// Number of computed values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bars the indicator consumes before producing its first value.
+    pub fn lookback(&self) -> usize {
+        self.lookback
+    }
+
+    /// Number of input bars the indicator was calculated over.
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// Range of input bars that have a computed value.
+    pub fn input_range(&self) -> Range<usize> {
+        self.begin_index..self.begin_index + self.len
+    }
+
+    /// Maps a position in the output back to the input bar it was computed for.
+    pub fn input_index(&self, output_index: usize) -> Option<usize> {
+        if output_index < self.len {
+            Some(self.begin_index + output_index)
+        } else {
+            None
+        }
+    }
+
+    /// Maps an input bar to its position in the output, if a value was computed for it.
+    pub fn output_index(&self, input_index: usize) -> Option<usize> {
+        if self.input_range().contains(&input_index) {
+            Some(input_index - self.begin_index)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Outputs> IndicatorOutput<T> {
+    /// Returns the values padded to the same length as the input, so index `i`
+    /// of the result belongs to input bar `i`.
+    pub fn into_aligned(self) -> T {
+        self.values.pad(self.begin_index, self.input_len)
+    }
+
+    /// Adds the `len` values computed for the input bars after those this output
+    /// covers, now that the input has grown to `input_len` bars.
+    pub(crate) fn extend(mut self, tail: T, len: usize, input_len: usize) -> Self {
+        self.values.append(tail);
+        self.len += len;
+        self.input_len = input_len;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_between_output_and_input() {
+        let output = IndicatorOutput::new(vec![2.5, 3.5], 3, 2, 3, 5);
+
+        assert_eq!(output.input_range(), 3..5);
+        assert_eq!(output.input_index(0), Some(3));
+        assert_eq!(output.input_index(1), Some(4));
+        assert_eq!(output.input_index(2), None);
+
+        assert_eq!(output.output_index(2), None);
+        assert_eq!(output.output_index(3), Some(0));
+        assert_eq!(output.output_index(4), Some(1));
+        assert_eq!(output.output_index(5), None);
+    }
+
+    #[test]
+    fn aligns_real_output_with_nan() {
+        let output = IndicatorOutput::new(vec![2.5, 3.5], 3, 2, 3, 5);
+        let aligned = output.into_aligned();
+
+        assert_eq!(aligned.len(), 5);
+        assert!(aligned[..3].iter().all(|x| x.is_nan()));
+        assert_eq!(&aligned[3..], &[2.5, 3.5]);
+    }
+
+    #[test]
+    fn aligns_integer_outputs_with_sentinel() {
+        let output = IndicatorOutput::new((vec![1, 2], vec![3, 4]), 1, 2, 1, 3);
+        let (first, second) = output.into_aligned();
+
+        assert_eq!(first, vec![INTEGER_SENTINEL, 1, 2]);
+        assert_eq!(second, vec![INTEGER_SENTINEL, 3, 4]);
+    }
+
+    #[test]
+    fn extends_with_later_values() {
+        let output = IndicatorOutput::new((vec![1, 2], vec![3, 4]), 1, 2, 1, 3);
+        let output = output.extend((vec![5], vec![6]), 1, 4);
+
+        assert_eq!(output.values(), &(vec![1, 2, 5], vec![3, 4, 6]));
+        assert_eq!(output.input_range(), 1..4);
+        assert_eq!(output.input_len(), 4);
+    }
+
+    #[test]
+    fn empty_output_maps_nothing() {
+        let output: IndicatorOutput<Vec<f64>> = IndicatorOutput::new(vec![], 0, 0, 3, 2);
+
+        assert!(output.is_empty());
+        assert_eq!(output.input_index(0), None);
+        assert_eq!(output.output_index(0), None);
+    }
+}
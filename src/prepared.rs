@@ -0,0 +1,21 @@
+use rustta_bindgen::meta::params::param_holder::ParamHolder;
+
+/// An indicator whose TA-Lib function handle has been resolved and whose parameters
+/// have already been applied, so each calculation only has to set its inputs and outputs.
+///
+/// Created with the `prepare` method on any generated indicator.
+pub struct Prepared<I> {
+    pub(crate) indicator: I,
+    pub(crate) params: ParamHolder,
+}
+
+impl<I> Prepared<I> {
+    pub(crate) fn new(indicator: I, params: ParamHolder) -> Self {
+        Self { indicator, params }
+    }
+
+    /// The indicator this was prepared from.
+    pub fn indicator(&self) -> &I {
+        &self.indicator
+    }
+}
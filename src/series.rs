@@ -0,0 +1,316 @@
+use std::ops::Range;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::{
+    error::SeriesError,
+    input::{Close, High, Length, Low, Ohlcv, Open, OpenInterest, Timestamp, Volume},
+};
+
+/// A single bar of an [`OhlcvSeries`].
+///
+/// Timestamps are plain integers, so any unit works as long as a series sticks to
+/// one. The `chrono` conversions treat them as Unix milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub open_interest: Option<f64>,
+}
+
+#[cfg(feature = "chrono")]
+impl Bar {
+    pub fn datetime(&self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_millis_opt(self.timestamp).single()
+    }
+}
+
+/// Owned bar data, stored column by column so it can be passed to any indicator.
+///
+/// Timestamps must be strictly increasing, and either every bar or none has an open
+/// interest, depending on how the series was created.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OhlcvSeries {
+    timestamp: Vec<i64>,
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+    open_interest: Option<Vec<f64>>,
+}
+
+impl OhlcvSeries {
+    /// Creates an empty series of bars without open interest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty series of bars that all carry an open interest.
+    pub fn with_open_interest() -> Self {
+        Self {
+            open_interest: Some(Vec::new()),
+            ..Self::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamp.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_empty()
+    }
+
+    pub fn has_open_interest(&self) -> bool {
+        self.open_interest.is_some()
+    }
+
+    /// Appends a bar after checking it fits the series.
+    pub fn push(&mut self, bar: Bar) -> Result<(), SeriesError> {
+        let index = self.len();
+
+        if let Some(&previous) = self.timestamp.last() {
+            if bar.timestamp <= previous {
+                return Err(SeriesError::TimestampOutOfOrder {
+                    index,
+                    timestamp: bar.timestamp,
+                    previous,
+                });
+            }
+        }
+
+        match (&mut self.open_interest, bar.open_interest) {
+            (Some(column), Some(open_interest)) => column.push(open_interest),
+            (None, None) => {}
+            (Some(_), None) => return Err(SeriesError::MissingOpenInterest { index }),
+            (None, Some(_)) => return Err(SeriesError::UnexpectedOpenInterest { index }),
+        }
+
+        self.timestamp.push(bar.timestamp);
+        self.open.push(bar.open);
+        self.high.push(bar.high);
+        self.low.push(bar.low);
+        self.close.push(bar.close);
+        self.volume.push(bar.volume);
+
+        Ok(())
+    }
+
+    /// Appends every bar, or none of them if one doesn't fit the series.
+    pub fn extend<I: IntoIterator<Item = Bar>>(&mut self, bars: I) -> Result<(), SeriesError> {
+        let len = self.len();
+
+        for bar in bars {
+            if let Err(e) = self.push(bar) {
+                self.truncate(len);
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shortens the series to its first `len` bars.
+    pub fn truncate(&mut self, len: usize) {
+        self.timestamp.truncate(len);
+        self.open.truncate(len);
+        self.high.truncate(len);
+        self.low.truncate(len);
+        self.close.truncate(len);
+        self.volume.truncate(len);
+
+        if let Some(column) = &mut self.open_interest {
+            column.truncate(len);
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Bar> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(Bar {
+            timestamp: self.timestamp[index],
+            open: self.open[index],
+            high: self.high[index],
+            low: self.low[index],
+            close: self.close[index],
+            volume: self.volume[index],
+            open_interest: self.open_interest.as_ref().map(|column| column[index]),
+        })
+    }
+
+    /// Borrows the whole series. Open interest is empty if the series has none.
+    pub fn as_ohlcv(&self) -> Ohlcv<'_> {
+        self.slice(0..self.len())
+    }
+
+    /// Borrows the bars in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds, like slicing a `Vec`.
+    pub fn slice(&self, range: Range<usize>) -> Ohlcv<'_> {
+        Ohlcv {
+            open: &self.open[range.clone()],
+            high: &self.high[range.clone()],
+            low: &self.low[range.clone()],
+            close: &self.close[range.clone()],
+            volume: &self.volume[range.clone()],
+            open_interest: match &self.open_interest {
+                Some(column) => &column[range.clone()],
+                None => &[],
+            },
+            timestamp: &self.timestamp[range],
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn datetime(&self, index: usize) -> Option<DateTime<Utc>> {
+        self.get(index).and_then(|bar| bar.datetime())
+    }
+}
+
+impl Length for &OhlcvSeries {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Open for &OhlcvSeries {
+    fn open(&self) -> &[f64] {
+        &self.open
+    }
+}
+impl High for &OhlcvSeries {
+    fn high(&self) -> &[f64] {
+        &self.high
+    }
+}
+impl Low for &OhlcvSeries {
+    fn low(&self) -> &[f64] {
+        &self.low
+    }
+}
+impl Close for &OhlcvSeries {
+    fn close(&self) -> &[f64] {
+        &self.close
+    }
+}
+impl Volume for &OhlcvSeries {
+    fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+}
+impl OpenInterest for &OhlcvSeries {
+    fn open_interest(&self) -> &[f64] {
+        match &self.open_interest {
+            Some(column) => column,
+            None => &[],
+        }
+    }
+}
+impl Timestamp for &OhlcvSeries {
+    fn timestamp(&self) -> &[i64] {
+        &self.timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(timestamp: i64, close: f64) -> Bar {
+        Bar {
+            timestamp,
+            open: close - 0.5,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 100.0,
+            open_interest: None,
+        }
+    }
+
+    #[test]
+    fn pushes_and_borrows_bars() {
+        let mut series = OhlcvSeries::new();
+        series.push(bar(1, 10.0)).unwrap();
+        series.extend(vec![bar(2, 11.0), bar(3, 12.0)]).unwrap();
+
+        assert_eq!(series.len(), 3);
+        assert_eq!(series.get(1), Some(bar(2, 11.0)));
+        assert_eq!(series.get(3), None);
+
+        let ohlcv = series.as_ohlcv();
+        assert_eq!(ohlcv.close, &[10.0, 11.0, 12.0]);
+        assert_eq!(ohlcv.timestamp, &[1, 2, 3]);
+        assert!(ohlcv.open_interest.is_empty());
+
+        let ohlcv = series.slice(1..3);
+        assert_eq!(ohlcv.high, &[12.0, 13.0]);
+        assert_eq!(ohlcv.timestamp, &[2, 3]);
+    }
+
+    #[test]
+    fn rejects_out_of_order_timestamps() {
+        let mut series = OhlcvSeries::new();
+        series.push(bar(2, 10.0)).unwrap();
+
+        assert_eq!(
+            series.push(bar(2, 11.0)),
+            Err(SeriesError::TimestampOutOfOrder {
+                index: 1,
+                timestamp: 2,
+                previous: 2
+            })
+        );
+
+        // Nothing is kept from a failed extend
+        assert!(series.extend(vec![bar(3, 11.0), bar(1, 12.0)]).is_err());
+        assert_eq!(series.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn converts_timestamps_to_datetimes() {
+        let mut series = OhlcvSeries::new();
+        series.push(bar(1_600_000_000_000, 10.0)).unwrap();
+
+        let datetime = series.datetime(0).unwrap();
+        assert_eq!(datetime.timestamp(), 1_600_000_000);
+        assert_eq!(series.datetime(1), None);
+    }
+
+    #[test]
+    fn checks_open_interest() {
+        let mut series = OhlcvSeries::with_open_interest();
+        assert_eq!(
+            series.push(bar(1, 10.0)),
+            Err(SeriesError::MissingOpenInterest { index: 0 })
+        );
+
+        series
+            .push(Bar {
+                open_interest: Some(500.0),
+                ..bar(1, 10.0)
+            })
+            .unwrap();
+        assert_eq!((&series).open_interest(), &[500.0]);
+
+        let mut series = OhlcvSeries::new();
+        assert_eq!(
+            series.push(Bar {
+                open_interest: Some(500.0),
+                ..bar(1, 10.0)
+            }),
+            Err(SeriesError::UnexpectedOpenInterest { index: 0 })
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use rustta_bindgen::{direct, unstable};
+
+use crate::{bars::BarColumns, error::IndicatorError, input::InputFlags, prepared::Prepared};
+
+/// Bars an indicator's values depend on, as set in its parameters.
+pub(crate) enum History {
+    /// Only those of the lookback.
+    Lookback,
+    /// Those of the lookback, which includes the unstable periods of these functions.
+    Unstable(Vec<(&'static str, direct::TA_FuncUnstId)>),
+    /// Every bar before them.
+    Whole,
+}
+
+/// An indicator fed one bar at a time, created with the `stream` method on any
+/// generated indicator. Each indicator module also has an alias for its stream type,
+/// like `SmaStream`.
+///
+/// Pushing a bar returns the indicator's value for it, once enough bars have been
+/// pushed to cover the lookback.
+///
+/// Streams keep the last `lookback + 1` bars and calculate over those, which gives the
+/// values `calculate` returns for the same bars, up to rounding, for indicators that only
+/// look at their lookback.
+///
+/// Those TA-Lib flags with an unstable period, like `EMA` and `RSI`, and those built on
+/// them, like `MACD` or anything given an exponential moving average type, start from a
+/// guess at the first bar of the window instead. Their lookback includes the unstable
+/// period set with the indicator's `set_unstable_period`, and the error left from the
+/// guess shrinks by a factor of `1 - α` for every bar of it, with `α` the smoothing
+/// factor. After `n` bars values are within `(1 - α)^n` of the distance between the
+/// guess and the actual value, and identical to `calculate`'s after about `37 / α` bars:
+/// about `18 * (period + 1)` for exponential averages and `37 * period` for Wilder's
+/// smoothing in `RSI` or `ATR`. Creating their stream fails with
+/// [`IndicatorError::UnstablePeriodNotSet`] while the period is the default `0`, and
+/// streams read the lookback when they are created, so set the period first.
+///
+/// Indicators whose values depend on every bar before them, like running totals such as
+/// `OBV` or the path dependent `SAR`, can't be reproduced from a window, so creating
+/// their stream fails with [`IndicatorError::NotStreamable`]. Calculate them with
+/// `update` instead.
+pub struct Stream<I> {
+    pub(crate) prepared: Prepared<I>,
+    pub(crate) lookback: usize,
+    pub(crate) window: usize,
+    pub(crate) len: usize,
+    pub(crate) real: Vec<Vec<f64>>,
+    pub(crate) integer: Vec<Vec<i32>>,
+    pub(crate) price: Vec<BarColumns>,
+}
+
+impl<I> Stream<I> {
+    pub(crate) fn new(
+        prepared: Prepared<I>,
+        lookback: usize,
+        function: &'static str,
+        history: History,
+        real_inputs: usize,
+        integer_inputs: usize,
+        price_inputs: &[InputFlags],
+    ) -> Result<Self, IndicatorError> {
+        match history {
+            History::Lookback => {}
+            History::Unstable(functions) => {
+                if let Some(&(unstable, _)) =
+                    functions.iter().find(|&&(_, id)| unstable::period(id) == 0)
+                {
+                    return Err(IndicatorError::UnstablePeriodNotSet { function, unstable });
+                }
+            }
+            History::Whole => return Err(IndicatorError::NotStreamable { function }),
+        }
+
+        Ok(Self {
+            prepared,
+            lookback,
+            window: lookback + 1,
+            len: 0,
+            real: vec![Vec::new(); real_inputs],
+            integer: vec![Vec::new(); integer_inputs],
+            price: price_inputs
+                .iter()
+                .map(|&flags| BarColumns::with_flags(flags))
+                .collect(),
+        })
+    }
+
+    /// The indicator this stream was created from.
+    pub fn indicator(&self) -> &I {
+        self.prepared.indicator()
+    }
+
+    /// Number of bars the indicator consumes before producing its first value.
+    pub fn lookback(&self) -> usize {
+        self.lookback
+    }
+
+    /// Number of bars the stream keeps.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Number of bars currently kept.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Records that a bar was pushed onto every input and drops the oldest one if the
+    /// window is full. Returns whether there are enough bars for a value.
+    pub(crate) fn advance(&mut self) -> bool {
+        self.len += 1;
+
+        if self.len > self.window {
+            let excess = self.len - self.window;
+
+            for column in &mut self.real {
+                column.drain(..excess);
+            }
+            for column in &mut self.integer {
+                column.drain(..excess);
+            }
+            for columns in &mut self.price {
+                columns.remove_oldest(excess);
+            }
+            self.len = self.window;
+        }
+
+        self.len > self.lookback
+    }
+}
+
+/// Indicator outputs a stream can take the latest value from.
+pub(crate) trait LastValue {
+    type Value;
+
+    fn last_value(self) -> Option<Self::Value>;
+}
+
+impl<T: Copy> LastValue for Vec<T> {
+    type Value = T;
+
+    fn last_value(self) -> Option<T> {
+        self.last().copied()
+    }
+}
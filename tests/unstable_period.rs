@@ -0,0 +1,130 @@
+//! Tests setting TA-Lib's unstable periods, which every test in a binary shares, so they
+//! run in a binary of their own and take turns through `TEST_LOCK`.
+
+use std::{error::Error, sync::MutexGuard};
+
+use approx::assert_relative_eq;
+use rustta::indicators::momentum_indicators::{MacdBuilder, Rsi, RsiBuilder};
+use rustta::indicators::overlap_studies::{Ema, EmaBuilder};
+use rustta_bindgen::{direct::TA_FuncUnstId, unstable};
+
+/// Unstable period the tests warm `EMA` and `RSI` up on, long enough for their short
+/// periods to give the same values whichever bar they start from.
+const UNSTABLE_PERIOD: u32 = 600;
+
+/// Holds the lock on the unstable periods and sets them all back to `0` when dropped.
+struct UnstablePeriods {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl UnstablePeriods {
+    fn lock() -> Self {
+        Self {
+            _lock: unstable::TEST_LOCK
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+impl Drop for UnstablePeriods {
+    fn drop(&mut self) {
+        unsafe { unstable::set_period(TA_FuncUnstId::TA_FUNC_UNST_ALL, 0) }.unwrap();
+    }
+}
+
+fn closes(len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|i| 50.0 + 10.0 * (i as f64 / 7.0).sin() + i as f64 * 0.05)
+        .collect()
+}
+
+#[test]
+fn lookback_includes_unstable_period() -> Result<(), Box<dyn Error>> {
+    let _periods = UnstablePeriods::lock();
+    let ema = EmaBuilder::default().time_period(3).build()?;
+    let macd = MacdBuilder::default().build()?;
+    let macd_lookback = macd.lookback()?;
+    assert_eq!(ema.lookback()?, 2);
+
+    unsafe { Ema::set_unstable_period(100)? };
+    assert_eq!(Ema::unstable_period(), 100);
+    assert_eq!(ema.lookback()?, 102);
+    // MACD is built on EMA and warms up on its period
+    assert!(macd.lookback()? >= macd_lookback + 100);
+
+    Ok(())
+}
+
+#[test]
+fn stream_matches_calculate_once_warmed_up() -> Result<(), Box<dyn Error>> {
+    let _periods = UnstablePeriods::lock();
+    unsafe {
+        Ema::set_unstable_period(UNSTABLE_PERIOD)?;
+        Rsi::set_unstable_period(UNSTABLE_PERIOD)?;
+    }
+    let close = closes(1000);
+
+    let ema = EmaBuilder::default().time_period(3).build()?;
+    let mut stream = ema.stream()?;
+    let streamed = close
+        .iter()
+        .map(|&x| stream.push(x))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lookback = ema.lookback()?;
+    assert_eq!(lookback, 2 + UNSTABLE_PERIOD as usize);
+    assert_eq!(stream.window(), lookback + 1);
+    let aligned = ema.calculate_aligned(close.as_slice())?;
+    for (value, expected) in streamed[lookback..].iter().zip(&aligned[lookback..]) {
+        assert_relative_eq!(value.unwrap(), *expected, max_relative = 1e-9);
+    }
+
+    let rsi = RsiBuilder::default().time_period(5).build()?;
+    let mut stream = rsi.stream()?;
+    let streamed = close
+        .iter()
+        .map(|&x| stream.push(x))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lookback = rsi.lookback()?;
+    let aligned = rsi.calculate_aligned(close.as_slice())?;
+    for (value, expected) in streamed[lookback..].iter().zip(&aligned[lookback..]) {
+        assert_relative_eq!(value.unwrap(), *expected, max_relative = 1e-9);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn update_matches_calculate_with_unstable_period() -> Result<(), Box<dyn Error>> {
+    let _periods = UnstablePeriods::lock();
+    unsafe {
+        Ema::set_unstable_period(UNSTABLE_PERIOD)?;
+        Rsi::set_unstable_period(UNSTABLE_PERIOD)?;
+    }
+
+    // These are calculated in full whatever the period, so they match exactly
+    let close = closes(2000);
+    let (first, all) = (&close[..1500], close.as_slice());
+
+    let ema = EmaBuilder::default().time_period(5).build()?;
+    assert!(ema.lookback()? < first.len());
+    let previous = ema.calculate(first)?;
+    assert_eq!(ema.update(previous, all)?, ema.calculate(all)?);
+
+    let rsi = RsiBuilder::default().time_period(5).build()?;
+    let mut prepared = rsi.prepare()?;
+    let previous = prepared.calculate(first)?;
+    assert_eq!(prepared.update(previous, all)?, rsi.calculate(all)?);
+
+    let macd = MacdBuilder::default()
+        .fast_period(3)
+        .slow_period(6)
+        .signal_period(3)
+        .build()?;
+    let previous = macd.calculate(first)?;
+    assert_eq!(macd.update(previous, all)?, macd.calculate(all)?);
+
+    Ok(())
+}